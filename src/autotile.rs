@@ -0,0 +1,62 @@
+// Blob autotiling, every tile picks one of 47 sprites from which of its 8 neighbours are filled
+
+pub const N: u8 = 1;
+pub const NE: u8 = 2;
+pub const E: u8 = 4;
+pub const SE: u8 = 8;
+pub const S: u8 = 16;
+pub const SW: u8 = 32;
+pub const W: u8 = 64;
+pub const NW: u8 = 128;
+
+/// Grid offset of every neighbour bit, north is up so it is `y - 1`
+pub const NEIGHBOURS: [(u8, (i32, i32)); 8] = [
+    (N, (0, -1)),
+    (NE, (1, -1)),
+    (E, (1, 0)),
+    (SE, (1, 1)),
+    (S, (0, 1)),
+    (SW, (-1, 1)),
+    (W, (-1, 0)),
+    (NW, (-1, -1)),
+];
+
+/// Number of sprites in a blob tileset
+pub const BLOB_TILE_COUNT: usize = 47;
+
+/// Every distinct mask left after `reduce_mask`, the position in this table is the offset of the
+/// tile in the tileset
+pub const BLOB_MASKS: [u8; BLOB_TILE_COUNT] = [
+    0, 1, 4, 5, 7, 16, 17, 20, 21, 23, 28, 29, 31, 64, 65, 68, 69, 71, 80, 81, 84, 85, 87, 92, 93,
+    95, 112, 113, 116, 117, 119, 124, 125, 127, 193, 197, 199, 209, 213, 215, 221, 223, 241, 245,
+    247, 253, 255,
+];
+
+// A corner only matters when both of the edges next to it are filled
+const CORNERS: [(u8, u8, u8); 4] = [(NE, N, E), (SE, S, E), (SW, S, W), (NW, N, W)];
+
+/// Mask of the filled neighbours of `(x, y)`
+pub fn neighbour_mask((x, y): (i32, i32), is_filled: impl Fn((i32, i32)) -> bool) -> u8 {
+    NEIGHBOURS
+        .iter()
+        .filter(|(_, (i, j))| is_filled((x + i, y + j)))
+        .fold(0, |mask, (bit, _)| mask | bit)
+}
+
+/// Clears the corners that can't be seen because an edge next to them is empty
+pub fn reduce_mask(mut mask: u8) -> u8 {
+    for (corner, a, b) in CORNERS {
+        if mask & a == 0 || mask & b == 0 {
+            mask &= !corner;
+        }
+    }
+    mask
+}
+
+/// Offset in a blob tileset of the tile for a neighbour mask
+pub fn blob_index(mask: u8) -> usize {
+    let mask = reduce_mask(mask);
+    BLOB_MASKS
+        .binary_search(&mask)
+        .expect("reduced masks are all in the blob table")
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+use std::collections::HashSet;
+
+#[cfg(test)]
+use crate::autotile::*;
+
+#[test]
+fn test_every_mask_has_a_tile() {
+    let mut used = HashSet::new();
+    for mask in 0..=255u8 {
+        let idx = blob_index(mask);
+        let tile_mask = BLOB_MASKS[idx];
+        used.insert(idx);
+
+        // Edges are always kept
+        assert_eq!(
+            tile_mask & (N | E | S | W),
+            mask & (N | E | S | W),
+            "{mask}"
+        );
+
+        // Corners are kept only when both edges next to them are filled
+        for (corner, a, b) in [(NE, N, E), (SE, S, E), (SW, S, W), (NW, N, W)] {
+            let visible = mask & corner != 0 && mask & a != 0 && mask & b != 0;
+            assert_eq!(tile_mask & corner != 0, visible, "{mask}");
+        }
+    }
+
+    assert_eq!(used.len(), BLOB_TILE_COUNT);
+}
+
+#[test]
+fn test_blob_masks_are_reduced() {
+    for (idx, mask) in BLOB_MASKS.iter().enumerate() {
+        assert_eq!(reduce_mask(*mask), *mask);
+        assert_eq!(blob_index(*mask), idx);
+    }
+}
+
+#[test]
+fn test_neighbour_mask() {
+    let filled = HashSet::from([(0, -1), (1, -1), (1, 0), (-1, 1)]);
+    let mask = neighbour_mask((0, 0), |pos| filled.contains(&pos));
+    assert_eq!(mask, N | NE | E | SW);
+    assert_eq!(BLOB_MASKS[blob_index(mask)], N | NE | E);
+
+    // Islet, peninsula, inner corner and solid ground
+    assert_eq!(blob_index(0), 0);
+    assert_eq!(BLOB_MASKS[blob_index(S)], S);
+    assert_eq!(BLOB_MASKS[blob_index(!NW)], !NW);
+    assert_eq!(blob_index(0xff), BLOB_TILE_COUNT - 1);
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::elevation::ElevationBand;
+use crate::rules::BiomeThresholds;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Biome {
+    #[default]
+    Ocean,
+    /// Sand between the sea and the land
+    Beach,
+    Desert,
+    Grassland,
+    Forest,
+    Swamp,
+    Taiga,
+    Snow,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BiomeCell {
+    pub pos: (i32, i32),
+    pub elevation: f64,
+    pub temperature: f64,
+    pub moisture: f64,
+    pub biome: Biome,
+    pub band: ElevationBand,
+}
+
+impl Biome {
+    /// Picks a biome from the three independent noise fields
+    pub fn classify(
+        elevation: f64,
+        temperature: f64,
+        moisture: f64,
+        thresholds: &BiomeThresholds,
+    ) -> Self {
+        if elevation <= thresholds.sea_level {
+            return Biome::Ocean;
+        }
+        if elevation < thresholds.shore_level {
+            return Biome::Beach;
+        }
+
+        if temperature < thresholds.snow_temperature {
+            Biome::Snow
+        } else if temperature < thresholds.taiga_temperature {
+            Biome::Taiga
+        } else if temperature > thresholds.desert_temperature
+            && moisture < thresholds.desert_moisture
+        {
+            Biome::Desert
+        } else if moisture > thresholds.swamp_moisture {
+            Biome::Swamp
+        } else if moisture > thresholds.forest_moisture {
+            Biome::Forest
+        } else {
+            Biome::Grassland
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+
+use crate::elevation::ElevationBand;
+use crate::water::WaterClass;
+use crate::worldgen::{GridRect, Region};
+use crate::*;
+
+const CHUNK_CELLS: usize = CHUNK_W * CHUNK_H;
+
+/// One bit for every cell of a chunk
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkBits {
+    words: Vec<u64>,
+}
+
+/// What the player can stand on in a chunk, kept while the chunk is loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkTiles {
+    pub ground: ChunkBits,
+    pub beach: ChunkBits,
+    pub shallow: ChunkBits,
+    pub deep_ocean: ChunkBits,
+    pub lake: ChunkBits,
+    pub hills: ChunkBits,
+    pub mountains: ChunkBits,
+    pub cliffs: ChunkBits,
+    /// Roads and bridges
+    pub roads: ChunkBits,
+}
+
+impl ChunkBits {
+    pub fn new() -> Self {
+        Self {
+            words: vec![0; CHUNK_CELLS.div_ceil(64)],
+        }
+    }
+
+    /// Sets the bit of every cell of `cells` that is in the chunk
+    pub fn from_cells<'a>(
+        chunk_pos: (i32, i32),
+        cells: impl IntoIterator<Item = &'a (i32, i32)>,
+    ) -> Self {
+        let mut bits = Self::new();
+        let rect = GridRect::from_chunk(chunk_pos);
+        for pos in cells {
+            if rect.contains(*pos) {
+                bits.insert(*pos);
+            }
+        }
+        bits
+    }
+
+    /// `pos` can be any cell of the world, it is wrapped into the chunk
+    pub fn contains(&self, pos: (i32, i32)) -> bool {
+        let idx = cell_index(pos);
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    pub fn insert(&mut self, pos: (i32, i32)) {
+        let idx = cell_index(pos);
+        self.words[idx / 64] |= 1 << (idx % 64);
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.words.capacity() * std::mem::size_of::<u64>()
+    }
+}
+
+impl Default for ChunkBits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkTiles {
+    /// `region` must have been generated for the chunk at `chunk_pos`
+    pub fn from_region(chunk_pos: (i32, i32), region: &Region) -> Self {
+        let water_of = |class: WaterClass| -> HashSet<(i32, i32)> {
+            region
+                .water
+                .iter()
+                .filter(|(_, c)| **c == class)
+                .map(|(pos, _)| *pos)
+                .collect()
+        };
+        let band_of = |band: ElevationBand| -> HashSet<(i32, i32)> {
+            region
+                .biomes
+                .iter()
+                .filter(|cell| cell.band == band && region.ground.contains(&cell.pos))
+                .map(|cell| cell.pos)
+                .collect()
+        };
+
+        Self {
+            ground: ChunkBits::from_cells(chunk_pos, &region.ground),
+            beach: ChunkBits::from_cells(chunk_pos, &region.beach),
+            shallow: ChunkBits::from_cells(chunk_pos, &water_of(WaterClass::Shallow)),
+            deep_ocean: ChunkBits::from_cells(chunk_pos, &water_of(WaterClass::DeepOcean)),
+            lake: ChunkBits::from_cells(chunk_pos, &water_of(WaterClass::Lake)),
+            hills: ChunkBits::from_cells(chunk_pos, &band_of(ElevationBand::Hills)),
+            mountains: ChunkBits::from_cells(chunk_pos, &band_of(ElevationBand::Mountains)),
+            cliffs: ChunkBits::from_cells(chunk_pos, &region.cliffs),
+            roads: ChunkBits::from_cells(chunk_pos, &region.roads),
+        }
+    }
+
+    pub fn water_at(&self, pos: (i32, i32)) -> Option<WaterClass> {
+        if self.shallow.contains(pos) {
+            Some(WaterClass::Shallow)
+        } else if self.deep_ocean.contains(pos) {
+            Some(WaterClass::DeepOcean)
+        } else if self.lake.contains(pos) {
+            Some(WaterClass::Lake)
+        } else {
+            None
+        }
+    }
+
+    /// Band of a ground cell, water is always lowland
+    pub fn band_at(&self, pos: (i32, i32)) -> ElevationBand {
+        if self.mountains.contains(pos) {
+            ElevationBand::Mountains
+        } else if self.hills.contains(pos) {
+            ElevationBand::Hills
+        } else {
+            ElevationBand::Lowland
+        }
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + [
+                &self.ground,
+                &self.beach,
+                &self.shallow,
+                &self.deep_ocean,
+                &self.lake,
+                &self.hills,
+                &self.mountains,
+                &self.cliffs,
+                &self.roads,
+            ]
+            .iter()
+            .map(|bits| bits.size_in_bytes())
+            .sum::<usize>()
+    }
+}
+
+fn cell_index((x, y): (i32, i32)) -> usize {
+    let local_x = x.rem_euclid(CHUNK_W as i32) as usize;
+    let local_y = y.rem_euclid(CHUNK_H as i32) as usize;
+    local_y * CHUNK_W + local_x
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+use std::collections::HashSet;
+
+#[cfg(test)]
+use crate::{chunk_tiles::*, coords::*, worldgen::*, *};
+
+#[test]
+fn test_chunk_bits_roundtrip() {
+    for chunk_pos in [(0, 0), (-1, 0), (2, -3)] {
+        let rect = GridRect::from_chunk(chunk_pos);
+        let cells = HashSet::from([
+            (rect.x, rect.y),
+            (rect.x + CHUNK_W as i32 - 1, rect.y + 7),
+            (rect.x + 13, rect.y + CHUNK_H as i32 - 1),
+            // Outside of the chunk, ignored
+            (rect.x - 1, rect.y),
+        ]);
+
+        let bits = ChunkBits::from_cells(chunk_pos, &cells);
+        assert_eq!(bits.len(), 3);
+        for x in rect.x..rect.end().0 {
+            for y in rect.y..rect.end().1 {
+                assert_eq!(bits.contains((x, y)), cells.contains(&(x, y)));
+                assert_eq!(GridPos::new(x, y).chunk(), ChunkPos::from(chunk_pos));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_chunk_tiles_match_region() {
+    let generator = WorldGenerator::new(1234);
+    let chunk_pos = (-1, 0);
+    let region = generator.generate_chunk(chunk_pos);
+    let tiles = ChunkTiles::from_region(chunk_pos, &region);
+
+    let rect = region.rect;
+    for x in rect.x..rect.end().0 {
+        for y in rect.y..rect.end().1 {
+            assert_eq!(
+                tiles.ground.contains((x, y)),
+                region.ground.contains(&(x, y))
+            );
+            assert_eq!(tiles.beach.contains((x, y)), region.beach.contains(&(x, y)));
+            assert_eq!(tiles.water_at((x, y)), region.water_at((x, y)));
+            assert_eq!(
+                tiles.cliffs.contains((x, y)),
+                region.cliffs.contains(&(x, y))
+            );
+            if region.ground.contains(&(x, y)) {
+                assert_eq!(tiles.band_at((x, y)), region.biome_at((x, y)).unwrap().band);
+            }
+        }
+    }
+
+    // Nine bitsets are far smaller than a set of tuples
+    assert!(tiles.size_in_bytes() < 14_500);
+}
@@ -0,0 +1,10 @@
+use arboard::Clipboard;
+
+/// Text on the system clipboard, `None` if it is empty or can't be read.
+///
+/// On X11 reading waits for the program that owns the clipboard to answer, so this is meant to run
+/// off the main thread.
+pub fn paste_text() -> Option<String> {
+    let text = Clipboard::new().ok()?.get_text().ok()?;
+    (!text.is_empty()).then_some(text)
+}
@@ -2,10 +2,14 @@
 pub const TILE_W: usize = 6;
 pub const TILE_H: usize = 8;
 pub const SPRITE_SHEET_W: usize = 8;
-pub const SPRITE_SHEET_H: usize = 9;
+pub const SPRITE_SHEET_H: usize = 23;
 pub const SPRITE_SCALE_FACTOR: usize = 5;
 pub const ONE_WINDOWED_HOUSE_SPRITE_INDEX: usize = 17;
 pub const FOUR_WINDOWED_HOUSE_SPRITE_INDEX: usize = 18;
+pub const INTERACTIVE_SPRITE_INDICES: [usize; 2] = [
+    ONE_WINDOWED_HOUSE_SPRITE_INDEX,
+    FOUR_WINDOWED_HOUSE_SPRITE_INDEX,
+];
 pub const PLAYER_SPRITE_INDEX: usize = 56;
 pub const SPRITE_SHEET_PATH: &str = "sprite-sheet.png";
 pub const SPRITE_PADDING: f32 = 2.0;
@@ -23,13 +27,28 @@ pub const BG_COLOR: (u8, u8, u8) = (181, 212, 220);
 // Chunk
 pub const CHUNK_W: usize = 120;
 pub const CHUNK_H: usize = 100;
+pub const CHUNK_SPAWNS_PER_FRAME: usize = 1;
+pub const CHUNK_LOAD_RADIUS: u32 = 1;
+pub const CHUNK_UNLOAD_RADIUS: u32 = 2;
+pub const CHUNK_PRELOAD_AHEAD: u32 = 1;
+pub const CHUNK_LOD_RADIUS: u32 = 4;
+pub const LOD_ZOOM_SCALE: f32 = 3.0;
+pub const LOD_Z_INDEX: f32 = -1.0;
+
+// Terrain
+pub const TERRAIN_RULES_PATH: &str = "terrain.rules.ron";
 
 // Player
 pub const PLAYER_SPEED: f32 = 1.0;
 pub const PLAYER_FISH_SPEED: f32 = 1.5;
+pub const PLAYER_HILL_SPEED_FACTOR: f32 = 0.7;
+pub const PLAYER_MOUNTAIN_SPEED_FACTOR: f32 = 0.45;
+pub const PLAYER_ROAD_SPEED_FACTOR: f32 = 1.5;
 pub const PLAYER_ANIMATION_INTERVAL: f32 = 0.3;
 pub const WALK_TRAIL_TIMER: f32 = 1.2;
 pub const TRAIL_LIFE_SPAN: f32 = 5.0;
+pub const WALK_TRAIL_SPRITE_INDEX: usize = 50;
+pub const SAND_TRAIL_SPRITE_INDEX: usize = 52;
 pub const PLAYER_JUMP_TIME: f32 = 0.3;
 pub const PLAYER_IMAGE_PATH: &str = "player.png";
 
@@ -47,3 +66,4 @@ pub const MAZE_WIDTH: usize = 16;
 // Misc
 pub const ARG_DISABLE_FULLSCREEN: &str = "no-fullscreen";
 pub const SEED_FILE_PATH: &str = "seed";
+pub const SEED_INPUT_MAX_LEN: usize = 64;
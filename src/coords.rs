@@ -0,0 +1,121 @@
+use bevy::math::Vec2;
+
+use crate::*;
+
+/// A position in the game world in pixels, y up, with the origin at the centre of the grid
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WorldPos {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A cell of the terrain grid, y down, cell `(GRID_COLS / 2, GRID_ROWS / 2)` is drawn at the
+/// world origin
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GridPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A chunk of `CHUNK_W` by `CHUNK_H` grid cells, chunk `(0, 0)` starts at grid cell `(0, 0)`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChunkPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+// Size of a drawn tile in world pixels
+const CELL_W: f32 = (TILE_W * SPRITE_SCALE_FACTOR) as f32;
+const CELL_H: f32 = (TILE_H * SPRITE_SCALE_FACTOR) as f32;
+
+impl WorldPos {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// The cell whose tile is drawn under this position
+    pub fn grid(self) -> GridPos {
+        GridPos::new(
+            (self.x / CELL_W).round() as i32 + GRID_COLS as i32 / 2,
+            GRID_ROWS as i32 / 2 - (self.y / CELL_H).round() as i32,
+        )
+    }
+
+    pub fn chunk(self) -> ChunkPos {
+        self.grid().chunk()
+    }
+}
+
+impl GridPos {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Centre of the tile drawn for this cell
+    pub fn world(self) -> WorldPos {
+        WorldPos::new(
+            (self.x - GRID_COLS as i32 / 2) as f32 * CELL_W,
+            (GRID_ROWS as i32 / 2 - self.y) as f32 * CELL_H,
+        )
+    }
+
+    pub fn chunk(self) -> ChunkPos {
+        ChunkPos::new(
+            self.x.div_euclid(CHUNK_W as i32),
+            self.y.div_euclid(CHUNK_H as i32),
+        )
+    }
+}
+
+impl ChunkPos {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Top left cell of the chunk
+    pub fn origin(self) -> GridPos {
+        GridPos::new(self.x * CHUNK_W as i32, self.y * CHUNK_H as i32)
+    }
+
+    /// Chebyshev distance between two chunks, how many rings of chunks apart they are
+    pub fn distance(self, other: ChunkPos) -> u32 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+}
+
+impl From<Vec2> for WorldPos {
+    fn from(v: Vec2) -> Self {
+        Self::new(v.x, v.y)
+    }
+}
+
+impl From<WorldPos> for Vec2 {
+    fn from(pos: WorldPos) -> Self {
+        Vec2::new(pos.x, pos.y)
+    }
+}
+
+// World generation works on plain tuples
+impl From<(i32, i32)> for GridPos {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<GridPos> for (i32, i32) {
+    fn from(pos: GridPos) -> Self {
+        (pos.x, pos.y)
+    }
+}
+
+impl From<(i32, i32)> for ChunkPos {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<ChunkPos> for (i32, i32) {
+    fn from(pos: ChunkPos) -> Self {
+        (pos.x, pos.y)
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+use bevy::math::Vec2;
+
+#[cfg(test)]
+use crate::{coords::*, *};
+
+#[test]
+fn test_grid_world_roundtrip() {
+    let cells = [
+        (0, 0),
+        (GRID_COLS as i32 / 2, GRID_ROWS as i32 / 2),
+        (-1, -1),
+        (-537, 12),
+        (1999, -1601),
+    ];
+    for cell in cells {
+        let pos = GridPos::from(cell);
+        assert_eq!(pos.world().grid(), pos);
+        assert_eq!(<(i32, i32)>::from(pos), cell);
+    }
+
+    // The middle of the grid is drawn at the world origin, y goes up in the world and down the grid
+    let middle = GridPos::new(GRID_COLS as i32 / 2, GRID_ROWS as i32 / 2);
+    assert_eq!(middle.world(), WorldPos::new(0.0, 0.0));
+    let south_east = GridPos::new(middle.x + 1, middle.y + 1).world();
+    assert!(south_east.x > 0.0 && south_east.y < 0.0);
+}
+
+#[test]
+fn test_world_points_on_a_tile() {
+    let w = (TILE_W * SPRITE_SCALE_FACTOR) as f32;
+    let h = (TILE_H * SPRITE_SCALE_FACTOR) as f32;
+
+    // Anywhere on the drawn tile maps back to its cell, on either side of the origin
+    for pos in [
+        GridPos::new(500, 400),
+        GridPos::new(-3, -7),
+        GridPos::new(0, 1000),
+    ] {
+        let centre = pos.world();
+        for (dx, dy) in [(-0.49, -0.49), (0.49, 0.49), (-0.49, 0.49), (0.3, -0.1)] {
+            let point = WorldPos::new(centre.x + dx * w, centre.y + dy * h);
+            assert_eq!(point.grid(), pos);
+        }
+    }
+
+    let v = Vec2::new(-12.5, 40.0);
+    assert_eq!(Vec2::from(WorldPos::from(v)), v);
+}
+
+#[test]
+fn test_chunk_of_cells() {
+    let (w, h) = (CHUNK_W as i32, CHUNK_H as i32);
+    assert_eq!(GridPos::new(0, 0).chunk(), ChunkPos::new(0, 0));
+    assert_eq!(GridPos::new(w - 1, h - 1).chunk(), ChunkPos::new(0, 0));
+    assert_eq!(GridPos::new(w, h).chunk(), ChunkPos::new(1, 1));
+
+    // Negative cells belong to negative chunks, not to chunk zero
+    assert_eq!(GridPos::new(-1, -1).chunk(), ChunkPos::new(-1, -1));
+    assert_eq!(GridPos::new(-w, -h).chunk(), ChunkPos::new(-1, -1));
+    assert_eq!(GridPos::new(-w - 1, 0).chunk(), ChunkPos::new(-2, 0));
+
+    for chunk in [
+        ChunkPos::new(0, 0),
+        ChunkPos::new(-3, 2),
+        ChunkPos::new(5, -8),
+    ] {
+        let origin = chunk.origin();
+        assert_eq!(origin.chunk(), chunk);
+        assert_eq!(
+            GridPos::new(origin.x + w - 1, origin.y + h - 1).chunk(),
+            chunk
+        );
+        assert_eq!(GridPos::new(origin.x - 1, origin.y).chunk().x, chunk.x - 1);
+        assert_eq!(origin.world().chunk(), chunk);
+    }
+}
+
+#[test]
+fn test_chunk_distance() {
+    let a = ChunkPos::new(-2, 3);
+    assert_eq!(a.distance(a), 0);
+    assert_eq!(a.distance(ChunkPos::new(1, 2)), 3);
+    assert_eq!(ChunkPos::new(1, 2).distance(a), 3);
+    assert_eq!(a.distance(ChunkPos::new(-2, -4)), 7);
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rules::{Decoration, ElevationRules};
+
+/// How high the land is, bands are ordered from low to high
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum ElevationBand {
+    #[default]
+    Lowland,
+    Hills,
+    Mountains,
+}
+
+impl ElevationBand {
+    pub fn classify(elevation: f64, rules: &ElevationRules) -> Self {
+        if elevation >= rules.mountain_level {
+            ElevationBand::Mountains
+        } else if elevation >= rules.hill_level {
+            ElevationBand::Hills
+        } else {
+            ElevationBand::Lowland
+        }
+    }
+
+    /// Hills and mountains slow the player down
+    pub fn is_slope(&self) -> bool {
+        *self != ElevationBand::Lowland
+    }
+}
+
+impl ElevationRules {
+    /// Rolled before the decorations of the biome
+    pub fn decorations(&self, band: ElevationBand) -> &[Decoration] {
+        match band {
+            ElevationBand::Lowland => &[],
+            ElevationBand::Hills => &self.hills,
+            ElevationBand::Mountains => &self.mountains,
+        }
+    }
+
+    /// A cliff drops from `elevation` into a lower band by more than `cliff_drop`
+    pub fn is_cliff(&self, elevation: f64, nei_elevation: f64) -> bool {
+        ElevationBand::classify(nei_elevation, self) < ElevationBand::classify(elevation, self)
+            && elevation - nei_elevation > self.cliff_drop
+    }
+}
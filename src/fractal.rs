@@ -0,0 +1,174 @@
+use noise::{NoiseFn, OpenSimplex, Perlin};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::hash_2d;
+
+// Seed offsets of the two fields that displace samples when warping
+const WARP_X_SEED: u32 = 7919;
+const WARP_Y_SEED: u32 = 7927;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoiseKind {
+    #[default]
+    Perlin,
+    OpenSimplex,
+    Worley,
+}
+
+/// Displaces every sample by up to `strength` tiles along a second noise field of size `scale`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DomainWarp {
+    pub strength: f64,
+    pub scale: f64,
+}
+
+/// Fractal brownian motion over one of the base noises
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NoiseConfig {
+    pub kind: NoiseKind,
+    /// Size of the largest features, in tiles
+    pub scale: f64,
+    pub octaves: usize,
+    /// Frequency multiplier from one octave to the next
+    pub lacunarity: f64,
+    /// Amplitude multiplier from one octave to the next
+    pub persistence: f64,
+    /// Size of every octave in tiles, for stacks that don't follow `lacunarity`. When set, these
+    /// are sampled instead and `lacunarity` is unused, `scale` and `octaves` must match the first
+    /// size and the number of sizes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub octave_scales: Vec<f64>,
+    #[serde(default)]
+    pub warp: Option<DomainWarp>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Source {
+    Perlin(Perlin),
+    OpenSimplex(OpenSimplex),
+    Worley(u32),
+}
+
+/// A `NoiseConfig` built for a seed, sampled in tile coordinates
+#[derive(Clone, Debug)]
+pub struct FractalNoise {
+    config: NoiseConfig,
+    source: Source,
+    warp_x: Perlin,
+    warp_y: Perlin,
+}
+
+impl NoiseConfig {
+    /// A single octave of `kind` noise
+    pub const fn single(kind: NoiseKind, scale: f64) -> Self {
+        Self {
+            kind,
+            scale,
+            octaves: 1,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            octave_scales: Vec::new(),
+            warp: None,
+        }
+    }
+
+    /// Why the config can't be used, if `octave_scales` contradicts `scale` or `octaves`
+    pub fn check(&self) -> Result<(), String> {
+        let Some(&first) = self.octave_scales.first() else {
+            return Ok(());
+        };
+        if self.octaves != self.octave_scales.len() {
+            return Err(format!(
+                "octaves is {} but octave_scales has {} sizes",
+                self.octaves,
+                self.octave_scales.len()
+            ));
+        }
+        if self.scale != first {
+            return Err(format!(
+                "scale is {} but octave_scales starts at {}",
+                self.scale, first
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn build(&self, seed: u32) -> FractalNoise {
+        FractalNoise::new(self.clone(), seed)
+    }
+}
+
+impl FractalNoise {
+    pub fn new(config: NoiseConfig, seed: u32) -> Self {
+        let source = match config.kind {
+            NoiseKind::Perlin => Source::Perlin(Perlin::new(seed)),
+            NoiseKind::OpenSimplex => Source::OpenSimplex(OpenSimplex::new(seed)),
+            NoiseKind::Worley => Source::Worley(seed),
+        };
+
+        Self {
+            config,
+            source,
+            warp_x: Perlin::new(seed.wrapping_add(WARP_X_SEED)),
+            warp_y: Perlin::new(seed.wrapping_add(WARP_Y_SEED)),
+        }
+    }
+
+    fn sample(&self, point: [f64; 2]) -> f64 {
+        match self.source {
+            Source::Perlin(perlin) => perlin.get(point),
+            Source::OpenSimplex(simplex) => simplex.get(point),
+            Source::Worley(seed) => worley(seed, point),
+        }
+    }
+}
+
+impl NoiseFn<f64, 2> for FractalNoise {
+    fn get(&self, [mut x, mut y]: [f64; 2]) -> f64 {
+        if let Some(warp) = self.config.warp {
+            let point = [x / warp.scale, y / warp.scale];
+            x += self.warp_x.get(point) * warp.strength;
+            y += self.warp_y.get(point) * warp.strength;
+        }
+
+        let mut amplitude = 1.0;
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut add_octave = |point: [f64; 2]| {
+            sum += self.sample(point) * amplitude;
+            total += amplitude;
+            amplitude *= self.config.persistence;
+        };
+        if self.config.octave_scales.is_empty() {
+            let mut frequency = 1.0 / self.config.scale;
+            for _ in 0..self.config.octaves.max(1) {
+                add_octave([x * frequency, y * frequency]);
+                frequency *= self.config.lacunarity;
+            }
+        } else {
+            for scale in self.config.octave_scales.iter() {
+                add_octave([x / scale, y / scale]);
+            }
+        }
+
+        sum / total
+    }
+}
+
+// Cellular noise, the distance to the nearest feature point mapped onto -1..1
+fn worley(seed: u32, [x, y]: [f64; 2]) -> f64 {
+    let (cell_x, cell_y) = (x.floor() as i64, y.floor() as i64);
+
+    let mut nearest = f64::MAX;
+    for i in -1..=1 {
+        for j in -1..=1 {
+            let (px, py) = (cell_x + i, cell_y + j);
+            let hash = hash_2d(seed, px, py) as u32;
+            let fx = px as f64 + (hash & 0xffff) as f64 / 65536.0;
+            let fy = py as f64 + (hash >> 16) as f64 / 65536.0;
+            nearest = nearest.min((fx - x).powi(2) + (fy - y).powi(2));
+        }
+    }
+
+    (nearest.sqrt() * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
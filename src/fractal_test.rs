@@ -0,0 +1,73 @@
+#[cfg(test)]
+use noise::{NoiseFn, Perlin};
+
+#[cfg(test)]
+use crate::{fractal::*, rules::TerrainRules};
+
+#[test]
+fn test_single_octave_matches_base_noise() {
+    let noise = NoiseConfig::single(NoiseKind::Perlin, 100.0).build(1234);
+    let perlin = Perlin::new(1234);
+
+    for (x, y) in [(0.0, 0.0), (12.5, -40.0), (333.0, 71.25)] {
+        let expected = perlin.get([x / 100.0, y / 100.0]);
+        assert!((noise.get([x, y]) - expected).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_octave_scales_match_original_elevation() {
+    let noise = TerrainRules::default().elevation.build(1234);
+    let perlin = Perlin::new(1234);
+
+    for (x, y) in [(0.0, 0.0), (12.0, -40.0), (333.0, 71.0), (-517.0, 260.0)] {
+        let sample = |scale: f64| perlin.get([x / scale, y / scale]);
+        let expected = (sample(100.5) + sample(53.5) + sample(43.5) + sample(23.5)) / 4.0;
+        assert_eq!(noise.get([x, y]), expected);
+    }
+}
+
+#[test]
+fn test_noise_kinds_stay_in_range() {
+    for kind in [NoiseKind::Perlin, NoiseKind::OpenSimplex, NoiseKind::Worley] {
+        let noise = NoiseConfig {
+            kind,
+            scale: 30.0,
+            octaves: 5,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            octave_scales: Vec::new(),
+            warp: Some(DomainWarp {
+                strength: 15.0,
+                scale: 60.0,
+            }),
+        }
+        .build(99);
+
+        for x in -50..50 {
+            for y in -50..50 {
+                let val = noise.get([x as f64 * 3.7, y as f64 * 2.3]);
+                assert!((-1.0..=1.0).contains(&val), "{:?} gave {}", kind, val);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_domain_warp_moves_samples() {
+    let config = NoiseConfig::single(NoiseKind::Perlin, 50.0);
+    let warped = NoiseConfig {
+        warp: Some(DomainWarp {
+            strength: 25.0,
+            scale: 40.0,
+        }),
+        ..config.clone()
+    };
+    let (plain, warped) = (config.build(7), warped.build(7));
+
+    let differs = (0..100).any(|i| {
+        let point = [i as f64 * 5.3, i as f64 * 1.7];
+        (plain.get(point) - warped.get(point)).abs() > 1e-6
+    });
+    assert!(differs);
+}
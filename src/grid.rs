@@ -1,11 +1,12 @@
 use bevy::{prelude::*, utils::HashSet};
 
+use crate::seed::Seed;
 use crate::utils::proc_gen_num;
 
 #[derive(Clone, Component, Debug)]
 pub struct Grid {
     pub cells: Vec<Vec<Cell>>,
-    pub seed: u32,
+    pub seed: Seed,
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -27,7 +28,7 @@ pub struct Cell {
 }
 
 impl Grid {
-    fn new_empty(width: usize, height: usize, seed: u32) -> Self {
+    fn new_empty(width: usize, height: usize, seed: Seed) -> Self {
         if height == 0 || width == 0 {
             panic!(
                 "maze height and width must be greater than zero. received height: {} and width: {}",
@@ -38,7 +39,7 @@ impl Grid {
         Grid { cells, seed }
     }
 
-    fn new_walled(width: usize, height: usize, seed: u32) -> Self {
+    fn new_walled(width: usize, height: usize, seed: Seed) -> Self {
         let mut grid = Self::new_empty(width, height, seed);
         for row in &mut grid.cells {
             for cell in row {
@@ -51,12 +52,12 @@ impl Grid {
         grid
     }
 
-    pub fn new_house(width: usize, height: usize, seed: u32) -> Self {
+    pub fn new_house(width: usize, height: usize, seed: Seed) -> Self {
         let mut house_grid = Self::new_empty(width, height, seed);
 
         for i in 2..15 {
-            let x = proc_gen_num(seed, i, height) + 1;
-            let y = proc_gen_num(seed, i + height + 1, width) + 1;
+            let x = proc_gen_num(seed.mazes(), i, height) + 1;
+            let y = proc_gen_num(seed.mazes(), i + height + 1, width) + 1;
             if let Some(cell) = house_grid.at(x, y) {
                 if i < 6 {
                     cell.cell_type = CellType::Chair;
@@ -71,7 +72,7 @@ impl Grid {
         house_grid
     }
 
-    pub fn new_maze(width: usize, height: usize, seed: u32) -> Self {
+    pub fn new_maze(width: usize, height: usize, seed: Seed) -> Self {
         let mut maze_grid = Self::new_walled(width, height, seed);
         walk_maze(&mut maze_grid);
         maze_grid
@@ -136,10 +137,7 @@ impl Grid {
     }
 
     pub fn clone_at(&self, x_index: usize, y_index: usize) -> Option<Cell> {
-        match self.clone().at(x_index, y_index) {
-            Some(c) => Some(c.clone()),
-            None => None,
-        }
+        self.clone().at(x_index, y_index).cloned()
     }
 
     pub fn at(&mut self, x_index: usize, y_index: usize) -> Option<&mut Cell> {
@@ -206,7 +204,7 @@ fn walk_maze(grid: &mut Grid) {
         let pos = (curr_x, curr_y);
         walked.insert(pos);
 
-        match get_next_pos(&grid, &curr_x, &curr_y, &walked) {
+        match get_next_pos(grid, &curr_x, &curr_y, &walked) {
             Some((next_x, next_y)) => {
                 // Carve out a passage between the two cells
                 if curr_x == next_x {
@@ -254,27 +252,27 @@ fn get_next_pos(
 ) -> Option<(usize, usize)> {
     let mut posib_next_pos_list: Vec<(usize, usize)> = vec![];
 
-    if x.to_owned() != 0 {
-        posib_next_pos_list.push((x.clone() - 1, y.clone()));
+    if *x != 0 {
+        posib_next_pos_list.push((*x - 1, *y));
     }
-    if x.to_owned() + 1 <= grid.max_x_index() {
-        posib_next_pos_list.push((x.clone() + 1, y.clone()));
+    if *x < grid.max_x_index() {
+        posib_next_pos_list.push((*x + 1, *y));
     }
-    if y.to_owned() != 0 {
-        posib_next_pos_list.push((x.clone(), y.clone() - 1));
+    if *y != 0 {
+        posib_next_pos_list.push((*x, *y - 1));
     }
-    if y.to_owned() + 1 <= grid.max_y_index() {
-        posib_next_pos_list.push((x.clone(), y.clone() + 1));
+    if *y < grid.max_y_index() {
+        posib_next_pos_list.push((*x, *y + 1));
     }
 
     posib_next_pos_list.retain(|pos| !walked.contains(pos));
 
-    if posib_next_pos_list.len() == 0 {
+    if posib_next_pos_list.is_empty() {
         return None;
     }
 
     // Procedurally generate next pos from number of cells walked & seed
-    let index = proc_gen_num(grid.seed, walked.len(), posib_next_pos_list.len());
+    let index = proc_gen_num(grid.seed.mazes(), walked.len(), posib_next_pos_list.len());
 
     if let Some(pos) = posib_next_pos_list.get(index) {
         return Some(pos.to_owned());
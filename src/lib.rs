@@ -1,11 +1,44 @@
+pub mod autotile;
+pub mod autotile_test;
+pub mod biome;
+pub mod chunk_tiles;
+pub mod chunk_tiles_test;
+pub mod clipboard;
 pub mod configs;
+pub mod coords;
+pub mod coords_test;
+pub mod elevation;
+pub mod fractal;
+pub mod fractal_test;
 pub mod grid;
+pub mod lod;
+pub mod lod_test;
 pub mod minigame;
 pub mod player;
+pub mod river;
+pub mod road;
+pub mod road_test;
+pub mod rules;
+pub mod rules_test;
+pub mod scatter;
+pub mod scatter_test;
+pub mod seed;
+pub mod seed_dialog;
+pub mod seed_test;
+pub mod settlement;
+pub mod settlement_test;
 pub mod sprite;
 pub mod sprite_test;
 pub mod terrain;
+pub mod terrain_map;
+pub mod terrain_map_test;
+pub mod terrain_test;
 pub mod tests;
+pub mod tilemap;
+pub mod tilemap_test;
 pub mod utils;
+pub mod water;
+pub mod worldgen;
+pub mod worldgen_test;
 
 pub use configs::*;
@@ -0,0 +1,56 @@
+use crate::biome::Biome;
+use crate::elevation::ElevationBand;
+use crate::water::WaterClass;
+use crate::worldgen::Region;
+
+/// Colour a cell is drawn with in the zoomed out map
+pub fn lod_color(region: &Region, pos: (i32, i32)) -> [u8; 4] {
+    if region.roads.contains(&pos) {
+        return [196, 165, 112, 255];
+    }
+    if region.rivers.contains(&pos) {
+        return [110, 170, 215, 255];
+    }
+    if let Some(class) = region.water_at(pos) {
+        return match class {
+            WaterClass::Shallow => [181, 212, 220, 255],
+            WaterClass::DeepOcean => [90, 135, 175, 255],
+            WaterClass::Lake => [100, 165, 170, 255],
+        };
+    }
+    if !region.ground.contains(&pos) {
+        return [181, 212, 220, 255];
+    }
+    if region.cliffs.contains(&pos) {
+        return [95, 88, 84, 255];
+    }
+
+    let cell = region.biome_at(pos);
+    if cell.is_some_and(|cell| cell.band == ElevationBand::Mountains) {
+        return [135, 133, 115, 255];
+    }
+    match cell.map(|cell| cell.biome) {
+        Some(Biome::Beach) => [240, 222, 175, 255],
+        Some(Biome::Desert) => [232, 205, 150, 255],
+        Some(Biome::Grassland) => [196, 224, 166, 255],
+        Some(Biome::Forest) => [125, 172, 112, 255],
+        Some(Biome::Swamp) => [112, 142, 104, 255],
+        Some(Biome::Taiga) => [152, 184, 156, 255],
+        Some(Biome::Snow) => [245, 245, 250, 255],
+        Some(Biome::Ocean) | None => [255, 241, 232, 255],
+    }
+}
+
+/// RGBA pixels of `region` with one pixel per cell, rows from the top of the region down
+pub fn bake_lod_pixels(region: &Region) -> Vec<u8> {
+    let rect = region.rect;
+    let (end_x, end_y) = rect.end();
+
+    let mut pixels = Vec::with_capacity(rect.w * rect.h * 4);
+    for y in rect.y..end_y {
+        for x in rect.x..end_x {
+            pixels.extend(lod_color(region, (x, y)));
+        }
+    }
+    pixels
+}
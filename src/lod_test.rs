@@ -0,0 +1,30 @@
+#[cfg(test)]
+use crate::{biome::Biome, lod::*, worldgen::*};
+
+#[test]
+fn test_lod_pixels() {
+    let region = WorldGenerator::new(1234).generate_chunk((0, 0));
+    let pixels = bake_lod_pixels(&region);
+    assert_eq!(pixels.len(), region.rect.w * region.rect.h * 4);
+
+    // Pixels are row major from the top left cell
+    let (x, y) = (region.rect.x + 5, region.rect.y + 3);
+    let idx = (3 * region.rect.w + 5) * 4;
+    assert_eq!(pixels[idx..idx + 4], lod_color(&region, (x, y)));
+
+    // Land and water never share a colour, bridges are drawn as road over both
+    let water = region
+        .water
+        .keys()
+        .filter(|pos| !region.bridges.contains(*pos))
+        .map(|pos| lod_color(&region, *pos));
+    let land: Vec<[u8; 4]> = region
+        .ground
+        .iter()
+        .filter(|pos| region.biome_at(**pos).unwrap().biome != Biome::Beach)
+        .map(|pos| lod_color(&region, *pos))
+        .collect();
+    for color in water {
+        assert!(!land.contains(&color));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, fs, io};
+use std::{env, fs};
 
 use bevy::{
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
@@ -7,10 +7,11 @@ use bevy::{
 };
 use bevy_pancam::{PanCam, PanCamPlugin};
 
+use island_procgen::seed_dialog::{SeedDialogPlugin, SeedDialogState};
+use island_procgen::*;
 use island_procgen::{minigame::MinigamePlugin, player::PlayerPlugin, terrain::TerrainPlugin};
-use island_procgen::{terrain::ResetTerrainEvent, *};
+use seed::Seed;
 use terrain::GenerationSeed;
-use utils::seed_from_seed_str;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -41,15 +42,19 @@ fn main() {
         .insert_resource(ClearColor(Color::rgba_u8(
             BG_COLOR.0, BG_COLOR.1, BG_COLOR.2, 0,
         )))
-        .add_plugins(PanCamPlugin::default())
+        .add_plugins(PanCamPlugin)
         .add_plugins(LogDiagnosticsPlugin::default())
-        .add_plugins(FrameTimeDiagnosticsPlugin::default())
+        .add_plugins(FrameTimeDiagnosticsPlugin)
         .add_plugins(TerrainPlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(MinigamePlugin)
-        .add_systems(Startup, spawn_camera)
-        .add_systems(Update, handle_settings_input)
-        .add_systems(Update, close_on_esc)
+        .add_plugins(SeedDialogPlugin)
+        .add_systems(Startup, (spawn_camera, log_seed))
+        // Esc closes the seed dialog first
+        .add_systems(
+            Update,
+            close_on_esc.run_if(in_state(SeedDialogState::Closed)),
+        )
         .run();
 }
 
@@ -59,41 +64,26 @@ fn spawn_camera(mut commands: Commands) {
         .insert(PanCam::default());
 }
 
-fn handle_settings_input(keys: Res<Input<KeyCode>>, mut writer: EventWriter<ResetTerrainEvent>) {
-    if !keys.just_pressed(KeyCode::Tab) {
-        return;
-    }
-
-    writer.send(ResetTerrainEvent);
-}
-
-fn init_seed() -> u32 {
-    let seed_str = if fs::exists(SEED_FILE_PATH).unwrap() {
-        let content =
-            String::from_utf8(fs::read(SEED_FILE_PATH).expect("Failed to read seed file"))
-                .expect("Failed to encode seed file");
-
-        if content.is_empty() {
-            prompt_seed_str_input()
-        } else {
-            content
-        }
+fn init_seed() -> Seed {
+    let content = if fs::exists(SEED_FILE_PATH).unwrap() {
+        String::from_utf8(fs::read(SEED_FILE_PATH).expect("Failed to read seed file"))
+            .expect("Failed to encode seed file")
     } else {
-        let input = prompt_seed_str_input();
-        fs::write(SEED_FILE_PATH, &input).expect("Failed to write seed file");
-
-        input
+        String::new()
     };
 
-    seed_from_seed_str(seed_str)
+    // Without a saved seed the world starts from a random one, a new one can be picked in game
+    let seed = if content.trim().is_empty() {
+        let seed = Seed::random();
+        fs::write(SEED_FILE_PATH, seed.to_string()).expect("Failed to write seed file");
+        seed
+    } else {
+        Seed::from_text(&content)
+    };
+    seed
 }
 
-fn prompt_seed_str_input() -> String {
-    let mut input = String::new();
-    println!("Please enter world seed, and then press ENTER:");
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Failed read to input");
-
-    input.trim().to_owned()
+// Logged once the app is running, the seed is read before Bevy's logger is set up
+fn log_seed(seed: Res<GenerationSeed>) {
+    info!("Seed: {}", seed.0);
 }
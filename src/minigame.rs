@@ -5,6 +5,7 @@ use bevy::prelude::*;
 use crate::{
     configs::*,
     grid::{CellType, Grid},
+    seed::Seed,
     utils::*,
 };
 
@@ -32,7 +33,7 @@ struct MinigameContainer;
 #[derive(Event)]
 pub struct SetMinigameEvent {
     pub minigame_state: MinigameState,
-    pub seed: u32,
+    pub seed: Seed,
 }
 
 pub struct MinigamePlugin;
@@ -53,7 +54,7 @@ impl Minigame {
         commands: &mut Commands,
         asset_server: &Res<AssetServer>,
         minigame_state: &MinigameState,
-        seed: u32,
+        seed: Seed,
     ) {
         match minigame_state {
             MinigameState::House => Self::spawn_new_house(commands, asset_server, seed),
@@ -62,7 +63,7 @@ impl Minigame {
         }
     }
 
-    fn spawn_new_house(commands: &mut Commands, asset_server: &Res<AssetServer>, seed: u32) {
+    fn spawn_new_house(commands: &mut Commands, asset_server: &Res<AssetServer>, seed: Seed) {
         Self::spawn_grid(
             commands,
             asset_server,
@@ -70,7 +71,7 @@ impl Minigame {
         );
     }
 
-    fn spawn_new_maze(commands: &mut Commands, asset_server: &Res<AssetServer>, seed: u32) {
+    fn spawn_new_maze(commands: &mut Commands, asset_server: &Res<AssetServer>, seed: Seed) {
         Self::spawn_grid(
             commands,
             asset_server,
@@ -270,7 +271,7 @@ fn handle_set_minigame(
     asset_server: Res<AssetServer>,
     mut set_minigame_event_reader: EventReader<SetMinigameEvent>,
 ) {
-    for event in set_minigame_event_reader.read() {
+    if let Some(event) = set_minigame_event_reader.read().next() {
         commands.insert_resource(NextState(Some(event.minigame_state)));
         Minigame::spawn_new(
             &mut commands,
@@ -278,7 +279,6 @@ fn handle_set_minigame(
             &event.minigame_state,
             event.seed,
         );
-        return;
     }
 }
 
@@ -332,8 +332,8 @@ fn handle_minigame_player_input(
 
     let px = MINIGAME_PLAYER_SPEED * speed_scale;
 
-    let mut new_left = style.left.clone();
-    let mut new_top = style.top.clone();
+    let mut new_left = style.left;
+    let mut new_top = style.top;
 
     if up_pressed {
         new_top = add_px_vals(style.top, Val::Px(-px));
@@ -441,11 +441,10 @@ fn minigame_player_pos_all_vertices(
     ]
 }
 
+type CloseButtonPressed = (Changed<Interaction>, With<CloseMinigameButton>);
+
 fn interact_with_close_minigame_button(
-    mut button_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<CloseMinigameButton>),
-    >,
+    mut button_query: Query<(&Interaction, &mut BackgroundColor), CloseButtonPressed>,
     mut minigame_state_next_state: ResMut<NextState<MinigameState>>,
 ) {
     if let Ok((interaction, mut background_color)) = button_query.get_single_mut() {
@@ -3,7 +3,12 @@ use bevy::{math::vec3, prelude::*, utils::Instant};
 use minigame::{MinigameState, SetMinigameEvent};
 use terrain::PUID;
 
-use crate::terrain::{GroundTiles, TileComponent};
+use crate::coords::{ChunkPos, WorldPos};
+use crate::elevation::ElevationBand;
+use crate::seed::Seed;
+use crate::seed_dialog::SeedDialogState;
+use crate::terrain::TileComponent;
+use crate::terrain_map::TerrainMap;
 use crate::utils::*;
 use crate::*;
 
@@ -28,9 +33,9 @@ struct WalkTrailTimer(Timer);
 #[derive(Resource)]
 struct DefaultAtlasHandle(pub Option<Handle<TextureAtlas>>);
 #[derive(Resource, Default)]
-pub struct CurrentPlayerChunkPos(pub (i32, i32));
+pub struct CurrentPlayerChunkPos(pub ChunkPos);
 #[derive(Event)]
-pub struct PlayerChunkUpdateEvent(pub (i32, i32));
+pub struct PlayerChunkUpdateEvent(pub ChunkPos);
 
 // TODO make this a state
 #[derive(Default, PartialEq, Debug)]
@@ -38,6 +43,8 @@ enum PlayerState {
     #[default]
     Idle,
     Walk,
+    /// Walking up hills or mountains
+    Climb,
     Jump(Instant),
     Swim,
 }
@@ -59,11 +66,15 @@ impl Plugin for PlayerPlugin {
             .add_systems(Update, camera_follow_player)
             .add_systems(
                 Update,
-                handle_player_hit_terrain.run_if(in_state(MinigameState::None)),
+                handle_player_hit_terrain
+                    .run_if(in_state(MinigameState::None))
+                    .run_if(in_state(SeedDialogState::Closed)),
             )
             .add_systems(
                 Update,
-                handle_player_input.run_if(in_state(MinigameState::None)),
+                handle_player_input
+                    .run_if(in_state(MinigameState::None))
+                    .run_if(in_state(SeedDialogState::Closed)),
             )
             .add_systems(Update, spawn_walk_trail)
             .add_systems(Update, update_player_chunk_pos)
@@ -109,7 +120,7 @@ fn setup(
 fn update_player_state(
     mut player_state: ResMut<CurrentPlayerState>,
     mut sprite_index: ResMut<PlayerSpriteIndex>,
-    ground_tiles: Res<GroundTiles>,
+    mut terrain: TerrainMap,
     mut player_query: Query<&Transform, With<Player>>,
 ) {
     if player_query.is_empty() {
@@ -117,10 +128,10 @@ fn update_player_state(
     }
 
     let transform = player_query.single_mut();
-    let (x, y) = (transform.translation.x, transform.translation.y);
-    let (x, y) = world_to_grid(x, y);
-    let (x, y) = center_to_top_left_grid(x, y);
-    let is_ground = ground_tiles.0.contains(&(x as i32, y as i32));
+    let cell = WorldPos::from(transform.translation.truncate()).grid();
+    // River cells are never ground, so the player swims across them like the sea unless there is
+    // a bridge
+    let is_ground = terrain.is_walkable(cell);
 
     if !is_ground && player_state.is_land() {
         player_state.0 = PlayerState::Jump(Instant::now());
@@ -129,18 +140,15 @@ fn update_player_state(
         player_state.0 = PlayerState::Jump(Instant::now());
     }
 
-    match player_state.0 {
-        PlayerState::Jump(jumped_at) => {
-            if jumped_at.elapsed().as_secs_f32() > PLAYER_JUMP_TIME {
-                player_state.0 = if is_ground {
-                    PlayerState::Idle
-                } else {
-                    PlayerState::Swim
-                };
-                sprite_index.0 = 0;
-            }
+    if let PlayerState::Jump(jumped_at) = player_state.0 {
+        if jumped_at.elapsed().as_secs_f32() > PLAYER_JUMP_TIME {
+            player_state.0 = if is_ground {
+                PlayerState::Idle
+            } else {
+                PlayerState::Swim
+            };
+            sprite_index.0 = 0;
         }
-        _ => {}
     }
 }
 
@@ -183,24 +191,20 @@ fn update_player_chunk_pos(
     }
 
     let transform = player_query.single();
-    let (x, y) = (transform.translation.x, transform.translation.y);
-    let (a, b) = world_to_grid(x, y);
-    let (a, b) = center_to_top_left_grid(a, b);
-    let (x, y) = grid_to_chunk(a, b);
-
-    let (old_x, old_y) = chunk_pos.0;
-    if old_x == x && old_y == y {
+    let new_chunk_pos = WorldPos::from(transform.translation.truncate()).chunk();
+    if chunk_pos.0 == new_chunk_pos {
         return;
     }
 
-    ev_chunk_update.send(PlayerChunkUpdateEvent((x, y)));
-    chunk_pos.0 = (x, y);
+    ev_chunk_update.send(PlayerChunkUpdateEvent(new_chunk_pos));
+    chunk_pos.0 = new_chunk_pos;
 }
 
 fn handle_player_input(
     mut player_state: ResMut<CurrentPlayerState>,
     mut player_direction: ResMut<PlayerDirection>,
     mut player_query: Query<&mut Transform, With<Player>>,
+    mut terrain: TerrainMap,
     keys: Res<Input<KeyCode>>,
 ) {
     if player_query.is_empty() {
@@ -254,20 +258,38 @@ fn handle_player_input(
         } else {
             player_angle
         };
+        let cell = terrain.tile_at(WorldPos::from(transform.translation.truncate()).grid());
+        let band = cell.band;
+
+        let slope_factor = match band {
+            ElevationBand::Lowland => 1.0,
+            ElevationBand::Hills => PLAYER_HILL_SPEED_FACTOR,
+            ElevationBand::Mountains => PLAYER_MOUNTAIN_SPEED_FACTOR,
+        };
+        let road_factor = if cell.road {
+            PLAYER_ROAD_SPEED_FACTOR
+        } else {
+            1.0
+        };
         let speed = if player_state.is_land() {
-            PLAYER_SPEED
+            PLAYER_SPEED * slope_factor * road_factor
         } else {
             PLAYER_FISH_SPEED
         };
         let new_pos = transform.translation + direction.normalize() * speed * speed_scale;
 
-        if !new_pos.is_nan() {
+        let next = terrain.tile_at(WorldPos::from(new_pos.truncate()).grid());
+        let blocked = next.blocks_step_from(&cell);
+
+        if !new_pos.is_nan() && !blocked {
             transform.translation = new_pos;
         }
 
         transform.rotation = Quat::from_rotation_z(sprite_angle);
         player_direction.0 = player_angle;
-        player_state.0 = if player_state.is_land() {
+        player_state.0 = if player_state.is_land() && band.is_slope() {
+            PlayerState::Climb
+        } else if player_state.is_land() {
             PlayerState::Walk
         } else {
             PlayerState::Swim
@@ -281,12 +303,14 @@ fn handle_player_input(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_walk_trail(
     time: Res<Time>,
     mut commands: Commands,
     player_state: Res<CurrentPlayerState>,
     player_angle: Res<PlayerDirection>,
     image_handle: Res<DefaultAtlasHandle>,
+    mut terrain: TerrainMap,
     mut timer: ResMut<WalkTrailTimer>,
     mut player_query: Query<&Transform, With<Player>>,
 ) {
@@ -300,10 +324,18 @@ fn spawn_walk_trail(
     }
 
     let transform = player_query.single_mut();
+    let cell = WorldPos::from(transform.translation.truncate()).grid();
+    // Footprints on the beach
+    let trail_sprite = if terrain.tile_at(cell).beach {
+        SAND_TRAIL_SPRITE_INDEX
+    } else {
+        WALK_TRAIL_SPRITE_INDEX
+    };
+
     commands.spawn((
         SpriteSheetBundle {
             texture_atlas: image_handle.0.clone().unwrap(),
-            sprite: TextureAtlasSprite::new(50),
+            sprite: TextureAtlasSprite::new(trail_sprite),
             transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR as f32 - 1.0))
                 .with_translation(vec3(transform.translation.x, transform.translation.y, 1.0))
                 .with_rotation(Quat::from_rotation_z(player_angle.0)),
@@ -379,12 +411,12 @@ fn handle_player_hit_terrain(
             if terrain_ta_sprite.index == ONE_WINDOWED_HOUSE_SPRITE_INDEX {
                 set_minigame_event_writer.send(SetMinigameEvent {
                     minigame_state: minigame::MinigameState::House,
-                    seed: puid.0,
+                    seed: Seed(puid.0),
                 });
             } else if terrain_ta_sprite.index == FOUR_WINDOWED_HOUSE_SPRITE_INDEX {
                 set_minigame_event_writer.send(SetMinigameEvent {
                     minigame_state: minigame::MinigameState::Maze,
-                    seed: puid.0,
+                    seed: Seed(puid.0),
                 });
             }
         }
@@ -393,21 +425,17 @@ fn handle_player_hit_terrain(
 
 impl CurrentPlayerState {
     fn is_land(&self) -> bool {
-        match self.0 {
-            PlayerState::Idle => true,
-            PlayerState::Walk => true,
-            _ => false,
-        }
+        matches!(
+            self.0,
+            PlayerState::Idle | PlayerState::Walk | PlayerState::Climb
+        )
     }
 
     fn is_walk(&self) -> bool {
-        self.0 == PlayerState::Walk
+        matches!(self.0, PlayerState::Walk | PlayerState::Climb)
     }
 
     fn is_jump(&self) -> bool {
-        match self.0 {
-            PlayerState::Jump(_) => true,
-            _ => false,
-        }
+        matches!(self.0, PlayerState::Jump(_))
     }
 }
@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::rules::RiverRules;
+use crate::utils::hash_2d;
+use crate::worldgen::GridRect;
+
+// Seed offset of the hash that jitters and picks river sources
+const RIVER_SEED: u32 = 104_729;
+
+/// River cells inside `rect`.
+///
+/// Rivers are traced downhill from highland sources on a fixed lattice, and every source that
+/// could reach `rect` is traced, so rivers stay continuous across chunk borders.
+pub fn river_cells(
+    seed: u32,
+    rules: &RiverRules,
+    sea_level: f64,
+    rect: GridRect,
+    height_at: impl Fn((i32, i32)) -> f64,
+) -> HashSet<(i32, i32)> {
+    // A wide river also covers the cell east of it, which can be the first column of `rect`
+    let flow_rect = GridRect::new(rect.x - 1, rect.y, rect.w + 1, rect.h);
+    let flow = flow_accumulation(seed, rules, sea_level, flow_rect, height_at);
+
+    let mut cells = HashSet::new();
+    for (pos, count) in flow.iter() {
+        cells.insert(*pos);
+        if *count >= rules.wide_flow {
+            cells.insert((pos.0 + 1, pos.1));
+        }
+    }
+
+    cells.retain(|pos| rect.contains(*pos));
+    cells
+}
+
+/// How many rivers pass through each cell of `rect`. Only sources close enough for a river of
+/// `max_length` to reach `rect` are traced.
+pub fn flow_accumulation(
+    seed: u32,
+    rules: &RiverRules,
+    sea_level: f64,
+    rect: GridRect,
+    height_at: impl Fn((i32, i32)) -> f64,
+) -> HashMap<(i32, i32), u32> {
+    let spacing = rules.source_spacing.max(1);
+    let reach = rules.max_length as i32 + 1;
+    let (end_x, end_y) = rect.end();
+
+    // Rivers from neighbouring sources soon run together, so heights are shared between traces
+    let mut heights = HashMap::new();
+    let mut flow = HashMap::new();
+    for i in (rect.x - reach).div_euclid(spacing)..=(end_x + reach).div_euclid(spacing) {
+        for j in (rect.y - reach).div_euclid(spacing)..=(end_y + reach).div_euclid(spacing) {
+            let hash = hash_2d(seed.wrapping_add(RIVER_SEED), i as i64, j as i64);
+            if (hash & 0xffff) as f64 / 65536.0 >= rules.source_chance {
+                continue;
+            }
+
+            let source = (
+                i * spacing + ((hash >> 16) % spacing as u64) as i32,
+                j * spacing + ((hash >> 32) % spacing as u64) as i32,
+            );
+            // Every step moves one cell, so the last cell of a river is `max_length - 1` steps
+            // from its source
+            let distance = (rect.x - source.0).max(source.0 - end_x + 1).max(0)
+                + (rect.y - source.1).max(source.1 - end_y + 1).max(0);
+            if distance >= rules.max_length as i32 {
+                continue;
+            }
+            let source_height = *heights.entry(source).or_insert_with(|| height_at(source));
+            if source_height < rules.source_elevation {
+                continue;
+            }
+
+            let path = trace_river(
+                source,
+                rules.max_length,
+                sea_level,
+                &mut heights,
+                &height_at,
+            );
+            for pos in path {
+                if rect.contains(pos) {
+                    *flow.entry(pos).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    flow
+}
+
+/// Follows the steepest descent from `source` until it reaches the sea or `max_length`.
+/// A river caught in a pit spills over the lowest cell it has not visited yet. Heights are
+/// remembered in `heights`, which can be shared by the traces of one run.
+pub fn trace_river(
+    source: (i32, i32),
+    max_length: usize,
+    sea_level: f64,
+    heights: &mut HashMap<(i32, i32), f64>,
+    height_at: impl Fn((i32, i32)) -> f64,
+) -> Vec<(i32, i32)> {
+    let mut height_at = |pos| *heights.entry(pos).or_insert_with(|| height_at(pos));
+    let mut path = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pos = source;
+    let mut height = height_at(pos);
+
+    while path.len() < max_length && height > sea_level {
+        path.push(pos);
+        visited.insert(pos);
+
+        let mut next: Option<((i32, i32), f64)> = None;
+        for (i, j) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let nei = (pos.0 + i, pos.1 + j);
+            if visited.contains(&nei) {
+                continue;
+            }
+
+            let nei_height = height_at(nei);
+            if next.is_none_or(|(_, h)| nei_height < h) {
+                next = Some((nei, nei_height));
+            }
+        }
+
+        match next {
+            Some((nei, nei_height)) => {
+                pos = nei;
+                height = nei_height;
+            }
+            None => break,
+        }
+    }
+
+    path
+}
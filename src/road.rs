@@ -0,0 +1,111 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::rules::RoadRules;
+use crate::worldgen::GridRect;
+
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Costs are summed as integers so that paths don't depend on float rounding
+const COST_SCALE: f64 = 100.0;
+
+/// A cell and how many cells of water led to it
+type Node = ((i32, i32), usize);
+
+/// A road between the wells of two villages
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Road {
+    pub from: u64,
+    pub to: u64,
+    /// From the well of `from` to the well of `to`
+    pub cells: Vec<(i32, i32)>,
+}
+
+/// What a road has to do to enter a cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crossing {
+    /// Land at `height`, with `cost` on top of the slope
+    Land {
+        height: f64,
+        cost: f64,
+    },
+    /// Only crossed by a bridge
+    Water,
+    Blocked,
+}
+
+/// Cheapest road from `start` to `goal` that stays inside `bounds`, found with A*.
+///
+/// Every step costs one, plus the climb between the two cells times `slope_cost` and the cost of
+/// the cell. Water is crossed by bridges of at most `max_bridge` cells.
+pub fn find_road(
+    start: (i32, i32),
+    goal: (i32, i32),
+    bounds: GridRect,
+    rules: &RoadRules,
+    crossing_at: impl Fn((i32, i32)) -> Crossing,
+) -> Option<Vec<(i32, i32)>> {
+    let mut crossings = HashMap::new();
+    let mut crossing =
+        |pos: (i32, i32)| -> Crossing { *crossings.entry(pos).or_insert_with(|| crossing_at(pos)) };
+    let heuristic = |(x, y): (i32, i32)| -> u64 {
+        ((x - goal.0).unsigned_abs() + (y - goal.1).unsigned_abs()) as u64 * COST_SCALE as u64
+    };
+
+    let mut best = HashMap::from([((start, 0), 0)]);
+    let mut came_from: HashMap<Node, Node> = HashMap::new();
+    let mut open = BinaryHeap::from([Reverse((heuristic(start), 0, start, 0))]);
+
+    while let Some(Reverse((_, cost, pos, bridge))) = open.pop() {
+        if pos == goal {
+            let mut node = (pos, bridge);
+            let mut path = vec![pos];
+            while let Some(prev) = came_from.get(&node) {
+                path.push(prev.0);
+                node = *prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if best.get(&(pos, bridge)).is_some_and(|c| *c < cost) {
+            continue;
+        }
+
+        let here = crossing(pos);
+        for (dx, dy) in DIRECTIONS {
+            let nei = (pos.0 + dx, pos.1 + dy);
+            if !bounds.contains(nei) {
+                continue;
+            }
+
+            let (step, nei_bridge) = match (here, crossing(nei)) {
+                (_, Crossing::Blocked) => continue,
+                (_, Crossing::Water) => {
+                    if bridge >= rules.max_bridge {
+                        continue;
+                    }
+                    (1.0 + rules.bridge_cost, bridge + 1)
+                }
+                (Crossing::Land { height, .. }, Crossing::Land { height: h, cost }) => {
+                    (1.0 + (h - height).abs() * rules.slope_cost + cost, 0)
+                }
+                (_, Crossing::Land { cost, .. }) => (1.0 + cost, 0),
+            };
+
+            let nei_cost = cost + (step * COST_SCALE).round() as u64;
+            if best.get(&(nei, nei_bridge)).is_some_and(|c| *c <= nei_cost) {
+                continue;
+            }
+            best.insert((nei, nei_bridge), nei_cost);
+            came_from.insert((nei, nei_bridge), (pos, bridge));
+            open.push(Reverse((
+                nei_cost + heuristic(nei),
+                nei_cost,
+                nei,
+                nei_bridge,
+            )));
+        }
+    }
+
+    None
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+use std::collections::HashMap;
+
+#[cfg(test)]
+use crate::{
+    coords::GridPos,
+    worldgen_test::{test_world, WORLD_RECT},
+};
+
+#[test]
+fn test_roads_join_villages() {
+    let (generator, _) = test_world();
+    let rect = WORLD_RECT;
+    // Roads reaching into `rect` can come from villages outside it
+    let settlements: HashMap<u64, (i32, i32)> = generator
+        .settlements_in(rect.expand(generator.rules().roads.max_length))
+        .iter()
+        .map(|s| (s.id, s.centre))
+        .collect();
+    let roads = generator.roads_in(rect);
+    assert!(!roads.is_empty());
+
+    let max_bridge = generator.rules().roads.max_bridge;
+    let sea_level = generator.rules().thresholds.sea_level;
+    for road in roads.iter() {
+        assert_eq!(road.cells.first(), settlements.get(&road.from));
+        assert_eq!(road.cells.last(), settlements.get(&road.to));
+
+        let mut bridge = 0;
+        for pair in road.cells.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            assert_eq!((x0 - x1).abs() + (y0 - y1).abs(), 1);
+
+            if generator.height_at((x1, y1)) <= sea_level {
+                bridge += 1;
+                assert!(bridge <= max_bridge);
+            } else {
+                bridge = 0;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_road_tiles_match_chunks() {
+    let (generator, region) = test_world();
+    assert!(!region.roads.is_empty());
+
+    let mut chunks = HashMap::new();
+    for pos in region.roads.iter() {
+        let chunk = chunks
+            .entry(GridPos::from(*pos).chunk())
+            .or_insert_with_key(|chunk_pos| generator.generate_chunk((*chunk_pos).into()));
+        assert!(chunk.roads.contains(pos));
+        assert_eq!(chunk.bridges.contains(pos), region.bridges.contains(pos));
+    }
+    for pos in region.bridges.iter() {
+        assert!(!region.ground.contains(pos));
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::biome::Biome;
+use crate::fractal::{NoiseConfig, NoiseKind};
+use crate::*;
+
+/// Everything that shapes the generated world, loaded from `TERRAIN_RULES_PATH`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TerrainRules {
+    pub world: WorldMode,
+    pub elevation: NoiseConfig,
+    pub temperature: NoiseConfig,
+    pub moisture: NoiseConfig,
+    /// How much colder the land gets as it rises
+    pub temperature_lapse: f64,
+    pub thresholds: BiomeThresholds,
+    /// First sprite of the 47 tile blob tileset the ground is drawn with
+    pub ground_tileset: usize,
+    /// Same as `ground_tileset`, for the beach
+    pub sand_tileset: usize,
+    pub ground_z_index: i32,
+    pub bands: ElevationRules,
+    pub rivers: RiverRules,
+    pub water: WaterRules,
+    pub settlements: SettlementRules,
+    pub roads: RoadRules,
+    pub decorations: HashMap<Biome, Vec<Decoration>>,
+}
+
+/// Whether the world goes on forever or is an archipelago inside `GRID_COLS` by `GRID_ROWS`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum WorldMode {
+    #[default]
+    Infinite,
+    Island(IslandFalloff),
+}
+
+/// Lowers the land towards the edges of the world rectangle
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IslandFalloff {
+    pub shape: FalloffShape,
+    /// Distance from the centre, as a fraction of the way to the edge, where the land starts sinking
+    pub start: f64,
+    /// How much the land is lowered at the edge and beyond
+    pub depth: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FalloffShape {
+    /// One round island in the middle
+    Radial,
+    /// Land reaches into the corners of the rectangle
+    Square,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeThresholds {
+    pub sea_level: f64,
+    pub shore_level: f64,
+    pub snow_temperature: f64,
+    pub taiga_temperature: f64,
+    pub desert_temperature: f64,
+    pub desert_moisture: f64,
+    pub swamp_moisture: f64,
+    pub forest_moisture: f64,
+}
+
+/// Hills and mountains, and the cliffs between them
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ElevationRules {
+    pub hill_level: f64,
+    pub mountain_level: f64,
+    /// Smallest drop into a lower band that makes a cliff
+    pub cliff_drop: f64,
+    pub cliff_sprite: usize,
+    pub cliff_z_index: i32,
+    pub hills: Vec<Decoration>,
+    pub mountains: Vec<Decoration>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RiverRules {
+    /// Distance in tiles between candidate river sources
+    pub source_spacing: i32,
+    /// Sources must be at least this high
+    pub source_elevation: f64,
+    pub source_chance: f64,
+    /// Longest river in tiles, also how far a chunk looks for sources
+    pub max_length: usize,
+    /// Cells fed by at least this many rivers are drawn two tiles wide
+    pub wide_flow: u32,
+    pub sprite: usize,
+    pub z_index: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaterRules {
+    /// Open sea above this elevation is shallow, below it is deep ocean
+    pub shallow_level: f64,
+    /// Bigger bodies of water are part of the open sea
+    pub max_lake_size: usize,
+    pub shallow_sprite: usize,
+    pub deep_sprite: usize,
+    pub lake_sprite: usize,
+    pub z_index: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SettlementRules {
+    /// Distance in tiles between candidate village sites
+    pub site_spacing: i32,
+    pub site_chance: f64,
+    /// Biomes a village can be centred in
+    pub biomes: Vec<Biome>,
+    /// How far the paths reach from the well, the fence runs one tile further out
+    pub radius: i32,
+    pub min_houses: usize,
+    pub max_houses: usize,
+    pub house_sprites: Vec<usize>,
+    pub well_sprite: usize,
+    pub fence_sprite: usize,
+    pub path_sprite: usize,
+    pub path_z_index: i32,
+    pub z_index: i32,
+}
+
+/// Roads between neighbouring villages, found with A*
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoadRules {
+    /// Villages further apart than this are not joined
+    pub max_length: i32,
+    /// How far a road may stray from the box between the villages it joins
+    pub margin: i32,
+    /// Cost of climbing or descending by one unit of elevation
+    pub slope_cost: f64,
+    pub forest_cost: f64,
+    /// Cost of every bridge tile
+    pub bridge_cost: f64,
+    /// Longest stretch of water a bridge can cross
+    pub max_bridge: usize,
+    /// First sprite of the 16 tile road tileset
+    pub tileset: usize,
+    pub bridge_sprite: usize,
+    pub z_index: i32,
+}
+
+/// A prop that can be placed on a tile of a biome, with `chance` per tile
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Decoration {
+    pub sprites: Vec<usize>,
+    pub z_index: i32,
+    pub chance: f64,
+    /// Props with the same sprites are never closer than this many tiles
+    pub spacing: f64,
+}
+
+impl TerrainRules {
+    /// Widest `spacing` of any decoration
+    pub fn max_spacing(&self) -> f64 {
+        self.decorations
+            .values()
+            .chain([&self.bands.hills, &self.bands.mountains])
+            .flatten()
+            .map(|decoration| decoration.spacing)
+            .fold(0.0, f64::max)
+    }
+
+    /// Decorations in the order they are rolled, the first one hit is placed
+    pub fn decorations(&self, biome: Biome) -> &[Decoration] {
+        match self.decorations.get(&biome) {
+            Some(decorations) => decorations,
+            None => &[],
+        }
+    }
+
+    /// Why the rules can't be used, if any of the noises contradict themselves
+    pub fn check(&self) -> Result<(), String> {
+        for (name, noise) in [
+            ("elevation", &self.elevation),
+            ("temperature", &self.temperature),
+            ("moisture", &self.moisture),
+        ] {
+            noise.check().map_err(|err| format!("{}: {}", name, err))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for TerrainRules {
+    fn default() -> Self {
+        let trees = vec![24, 25];
+        let broadleaf_trees = vec![24, 25, 26];
+        let dense_trees = vec![27];
+        let dead_trees = vec![28, 29];
+        let bones = vec![40, 41, 42, 43];
+        let rubble = vec![20];
+        let grass = vec![32];
+        let shells = vec![33];
+        let driftwood = vec![53];
+        let rocks = vec![34];
+
+        let decorations = HashMap::from([
+            (
+                Biome::Beach,
+                vec![
+                    Decoration::new(shells, 1, 0.02, 3.0),
+                    Decoration::new(driftwood, 1, 0.01, 4.0),
+                    Decoration::new(rocks, 2, 0.01, 4.0),
+                ],
+            ),
+            (
+                Biome::Desert,
+                vec![
+                    Decoration::new(bones.clone(), 1, 0.02, 4.0),
+                    Decoration::new(dead_trees.clone(), 3, 0.015, 4.0),
+                    Decoration::new(rubble, 1, 0.01, 3.0),
+                ],
+            ),
+            (
+                Biome::Grassland,
+                vec![
+                    Decoration::new(grass.clone(), 1, 0.04, 2.0),
+                    Decoration::new(broadleaf_trees.clone(), 3, 0.04, 3.0),
+                    Decoration::new(bones.clone(), 1, 0.003, 6.0),
+                ],
+            ),
+            (
+                Biome::Forest,
+                vec![
+                    Decoration::new(dense_trees.clone(), 5, 0.4, 1.5),
+                    Decoration::new(broadleaf_trees, 3, 0.3, 1.5),
+                ],
+            ),
+            (
+                Biome::Swamp,
+                vec![
+                    Decoration::new(grass, 1, 0.25, 1.5),
+                    Decoration::new(dense_trees, 5, 0.1, 2.0),
+                    Decoration::new(dead_trees.clone(), 3, 0.08, 3.0),
+                ],
+            ),
+            (
+                Biome::Taiga,
+                vec![
+                    Decoration::new(trees.clone(), 3, 0.45, 1.5),
+                    Decoration::new(dead_trees, 3, 0.03, 3.0),
+                ],
+            ),
+            (
+                Biome::Snow,
+                vec![
+                    Decoration::new(trees, 3, 0.03, 3.0),
+                    Decoration::new(bones, 1, 0.005, 6.0),
+                ],
+            ),
+        ]);
+
+        Self {
+            world: WorldMode::Infinite,
+            elevation: NoiseConfig {
+                kind: NoiseKind::Perlin,
+                scale: 100.5,
+                octaves: 4,
+                lacunarity: 2.0,
+                persistence: 1.0,
+                // The original four sizes, weighted equally
+                octave_scales: vec![100.5, 53.5, 43.5, 23.5],
+                warp: None,
+            },
+            temperature: NoiseConfig::single(NoiseKind::Perlin, 350.5),
+            moisture: NoiseConfig::single(NoiseKind::Perlin, 250.5),
+            temperature_lapse: 0.4,
+            thresholds: BiomeThresholds::default(),
+            ground_tileset: 72,
+            sand_tileset: 120,
+            ground_z_index: 0,
+            bands: ElevationRules::default(),
+            rivers: RiverRules::default(),
+            water: WaterRules::default(),
+            settlements: SettlementRules::default(),
+            roads: RoadRules::default(),
+            decorations,
+        }
+    }
+}
+
+impl WorldMode {
+    /// How much lower the land is at `(x, y)` than the noise says
+    pub fn falloff(&self, (x, y): (i32, i32)) -> f64 {
+        let falloff = match self {
+            WorldMode::Infinite => return 0.0,
+            WorldMode::Island(falloff) => falloff,
+        };
+
+        // Distance from the centre of the world, 1 at its edges
+        let half_w = GRID_COLS as f64 / 2.0;
+        let half_h = GRID_ROWS as f64 / 2.0;
+        let (dx, dy) = ((x as f64 - half_w) / half_w, (y as f64 - half_h) / half_h);
+        let distance = match falloff.shape {
+            FalloffShape::Radial => (dx * dx + dy * dy).sqrt(),
+            FalloffShape::Square => dx.abs().max(dy.abs()),
+        };
+
+        let t =
+            ((distance - falloff.start) / (1.0 - falloff.start).max(f64::EPSILON)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t) * falloff.depth
+    }
+}
+
+impl Default for BiomeThresholds {
+    fn default() -> Self {
+        Self {
+            sea_level: 0.0,
+            shore_level: 0.05,
+            snow_temperature: -0.4,
+            taiga_temperature: -0.2,
+            desert_temperature: 0.2,
+            desert_moisture: 0.0,
+            swamp_moisture: 0.3,
+            forest_moisture: 0.05,
+        }
+    }
+}
+
+impl Default for ElevationRules {
+    fn default() -> Self {
+        Self {
+            hill_level: 0.3,
+            mountain_level: 0.45,
+            cliff_drop: 0.025,
+            cliff_sprite: 35,
+            cliff_z_index: 1,
+            hills: vec![Decoration::new(vec![11, 12], 2, 0.06, 4.0)],
+            mountains: vec![Decoration::new(vec![8, 9, 10], 2, 0.2, 2.5)],
+        }
+    }
+}
+
+impl Default for RiverRules {
+    fn default() -> Self {
+        Self {
+            source_spacing: 32,
+            source_elevation: 0.3,
+            source_chance: 0.6,
+            max_length: 300,
+            wide_flow: 3,
+            sprite: 5,
+            z_index: 0,
+        }
+    }
+}
+
+impl Default for WaterRules {
+    fn default() -> Self {
+        Self {
+            shallow_level: -0.1,
+            max_lake_size: 2000,
+            shallow_sprite: 13,
+            deep_sprite: 14,
+            lake_sprite: 6,
+            z_index: 0,
+        }
+    }
+}
+
+impl Default for SettlementRules {
+    fn default() -> Self {
+        Self {
+            site_spacing: 80,
+            site_chance: 0.8,
+            biomes: vec![Biome::Grassland, Biome::Forest, Biome::Desert, Biome::Taiga],
+            radius: 5,
+            min_houses: 4,
+            max_houses: 8,
+            house_sprites: vec![16, 17, 18, 19],
+            well_sprite: 36,
+            fence_sprite: 37,
+            path_sprite: 44,
+            path_z_index: 1,
+            z_index: 8,
+        }
+    }
+}
+
+impl SettlementRules {
+    /// Furthest a village reaches from its well
+    pub fn reach(&self) -> i32 {
+        self.radius + 1
+    }
+}
+
+impl Default for RoadRules {
+    fn default() -> Self {
+        Self {
+            max_length: 200,
+            margin: 16,
+            slope_cost: 300.0,
+            forest_cost: 4.0,
+            bridge_cost: 4.0,
+            max_bridge: 4,
+            tileset: 168,
+            bridge_sprite: 51,
+            z_index: 1,
+        }
+    }
+}
+
+impl Decoration {
+    pub fn new(sprites: Vec<usize>, z_index: i32, chance: f64, spacing: f64) -> Self {
+        Self {
+            sprites,
+            z_index,
+            chance,
+            spacing,
+        }
+    }
+}
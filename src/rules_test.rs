@@ -0,0 +1,23 @@
+#[cfg(test)]
+use crate::rules::*;
+
+#[test]
+fn test_rules_file_matches_default() {
+    let rules: TerrainRules =
+        ron::de::from_str(include_str!("../assets/terrain.rules.ron")).unwrap();
+
+    assert_eq!(rules, TerrainRules::default());
+}
+
+#[test]
+fn test_octave_scales_must_match_scale_and_octaves() {
+    assert_eq!(TerrainRules::default().check(), Ok(()));
+
+    let mut rules = TerrainRules::default();
+    rules.elevation.octaves = 3;
+    assert!(rules.check().unwrap_err().starts_with("elevation"));
+
+    let mut rules = TerrainRules::default();
+    rules.elevation.scale = 80.0;
+    assert!(rules.check().is_err());
+}
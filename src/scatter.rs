@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use crate::rules::Decoration;
+use crate::utils::hash_2d;
+use crate::worldgen::GridRect;
+
+// Seed offset of the hash that rolls and ranks props
+const SCATTER_SEED: u32 = 15_485_863;
+
+/// A prop picked for a cell, before it is checked against its neighbours
+#[derive(Clone, Copy)]
+struct Candidate<'a> {
+    decoration: &'a Decoration,
+    hash: u64,
+}
+
+/// Props inside `rect`, as the cell, the decoration and the sprite picked for it.
+///
+/// Every cell rolls once against the decorations `decorations_at` gives for it, in order, the
+/// first one hit is its candidate. A candidate is kept only if it outranks every candidate of the
+/// same prop that is closer than the wider `spacing` of the two, so no two props of a kind are
+/// closer than that, even where biomes that space the prop differently meet. Rolls and ranks are
+/// hashed from the position. `max_spacing` must be at least the `spacing` of every decoration.
+pub fn scatter<'a, I>(
+    seed: u32,
+    rect: GridRect,
+    max_spacing: f64,
+    decorations_at: impl Fn((i32, i32)) -> I,
+) -> Vec<((i32, i32), &'a Decoration, usize)>
+where
+    I: Iterator<Item = &'a Decoration>,
+{
+    let margin = max_spacing.ceil() as i32;
+    let outer = rect.expand(margin);
+    let (end_x, end_y) = outer.end();
+
+    let mut candidates = HashMap::new();
+    for x in outer.x..end_x {
+        for y in outer.y..end_y {
+            let hash = hash_2d(seed.wrapping_add(SCATTER_SEED), x as i64, y as i64);
+            let chance = (hash & 0xffff_ffff) as f64 / 4_294_967_296.0;
+
+            let mut threshold = 0.0;
+            for decoration in decorations_at((x, y)) {
+                threshold += decoration.chance;
+                if chance < threshold {
+                    candidates.insert((x, y), Candidate { decoration, hash });
+                    break;
+                }
+            }
+        }
+    }
+
+    let rank = |pos: (i32, i32), candidate: &Candidate| (candidate.hash >> 32, pos);
+    let mut props = Vec::new();
+    let (end_x, end_y) = rect.end();
+    for (x, y) in (rect.x..end_x).flat_map(|x| (rect.y..end_y).map(move |y| (x, y))) {
+        let Some(candidate) = candidates.get(&(x, y)) else {
+            continue;
+        };
+
+        let outranked = (-margin..=margin).any(|dx| {
+            (-margin..=margin).any(|dy| {
+                if (dx, dy) == (0, 0) {
+                    return false;
+                }
+
+                let nei = (x + dx, y + dy);
+                candidates.get(&nei).is_some_and(|other| {
+                    let spacing = candidate.decoration.spacing.max(other.decoration.spacing);
+                    other.decoration.sprites == candidate.decoration.sprites
+                        && ((dx * dx + dy * dy) as f64) < spacing * spacing
+                        && rank(nei, other) > rank((x, y), candidate)
+                })
+            })
+        });
+        if outranked {
+            continue;
+        }
+
+        let sprites = &candidate.decoration.sprites;
+        let sprite = sprites[((candidate.hash >> 32) % sprites.len() as u64) as usize];
+        props.push(((x, y), candidate.decoration, sprite));
+    }
+
+    props
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+use std::collections::HashSet;
+
+#[cfg(test)]
+use crate::{rules::Decoration, scatter::scatter, worldgen::GridRect};
+
+#[test]
+fn test_scatter_keeps_spacing() {
+    let trees = Decoration::new(vec![24, 25], 3, 0.5, 3.0);
+    let rocks = Decoration::new(vec![34], 2, 0.3, 2.0);
+    let rect = GridRect::new(-50, -40, 100, 80);
+    let props = scatter(1234, rect, 3.0, |_| [&trees, &rocks].into_iter());
+    assert!(!props.is_empty());
+
+    for (i, (a, decoration, sprite)) in props.iter().enumerate() {
+        assert!(rect.contains(*a));
+        assert!(decoration.sprites.contains(sprite));
+
+        for (b, other, _) in props[i + 1..].iter() {
+            if other.sprites != decoration.sprites {
+                continue;
+            }
+            let distance = (((a.0 - b.0).pow(2) + (a.1 - b.1).pow(2)) as f64).sqrt();
+            assert!(distance >= decoration.spacing, "{:?} {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_scatter_is_continuous() {
+    let grass = Decoration::new(vec![32], 1, 0.3, 2.5);
+    let scatter_in = |rect| -> HashSet<((i32, i32), usize)> {
+        scatter(1234, rect, 2.5, |_| std::iter::once(&grass))
+            .into_iter()
+            .map(|(pos, _, sprite)| (pos, sprite))
+            .collect()
+    };
+
+    // Two halves give the same props as the whole
+    let whole = scatter_in(GridRect::new(0, 0, 60, 40));
+    let mut halves = scatter_in(GridRect::new(0, 0, 30, 40));
+    halves.extend(scatter_in(GridRect::new(30, 0, 30, 40)));
+    assert_eq!(whole, halves);
+}
+
+#[test]
+fn test_scatter_keeps_wider_spacing_at_biome_border() {
+    // The same trees, packed tightly west of x = 0 and spread out east of it
+    let forest = Decoration::new(vec![24, 25, 26], 3, 0.6, 1.5);
+    let grassland = Decoration::new(vec![24, 25, 26], 3, 0.6, 4.0);
+    let decorations_at = |(x, _): (i32, i32)| {
+        let decoration = if x < 0 { &forest } else { &grassland };
+        std::iter::once(decoration)
+    };
+    let rect = GridRect::new(-20, -20, 40, 40);
+    let props = scatter(1234, rect, 4.0, decorations_at);
+    assert!(props.iter().any(|((x, _), _, _)| *x < 0));
+    assert!(props.iter().any(|((x, _), _, _)| *x >= 0));
+
+    for (i, (a, decoration, _)) in props.iter().enumerate() {
+        for (b, other, _) in props[i + 1..].iter() {
+            let spacing = decoration.spacing.max(other.spacing);
+            let distance = (((a.0 - b.0).pow(2) + (a.1 - b.1).pow(2)) as f64).sqrt();
+            assert!(distance >= spacing, "{:?} {:?}", a, b);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::utils::mix_64;
+
+// 64-bit FNV-1a parameters
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// The seed a world is generated from.
+///
+/// Text typed as a seed is hashed whole with 64-bit FNV-1a, so every character and its place
+/// count. A seed displays as a code of 16 hex digits, `1A2B-3C4D-5E6F-7A8B`, and typing a code
+/// back in gives the same seed, so worlds can be shared by their code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Seed(pub u64);
+
+impl Seed {
+    /// Seed for text a player typed, surrounding whitespace is ignored
+    pub fn from_text(text: &str) -> Self {
+        let text = text.trim();
+        Self(parse_code(text).unwrap_or_else(|| fnv1a(text.as_bytes())))
+    }
+
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// Seed of the terrain noise and everything placed on it
+    pub fn terrain(self) -> u32 {
+        fold_32(self.stream("terrain"))
+    }
+
+    /// Seed of procedurally drawn sprites
+    pub fn sprites(self) -> u32 {
+        fold_32(self.stream("sprites"))
+    }
+
+    /// Seed of the minigame maze and house layouts
+    pub fn mazes(self) -> u64 {
+        self.stream("mazes")
+    }
+
+    // Streams are independent of each other, and different seeds give different streams
+    fn stream(self, name: &str) -> u64 {
+        mix_64(self.0 ^ fnv1a(name.as_bytes()))
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04X}-{:04X}-{:04X}-{:04X}",
+            self.0 >> 48,
+            (self.0 >> 32) & 0xffff,
+            (self.0 >> 16) & 0xffff,
+            self.0 & 0xffff,
+        )
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+// A displayed seed code, in either case
+fn parse_code(text: &str) -> Option<u64> {
+    let groups: Vec<&str> = text.split('-').collect();
+    let is_code = groups.len() == 4
+        && groups
+            .iter()
+            .all(|g| g.len() == 4 && g.chars().all(|c| c.is_ascii_hexdigit()));
+    if !is_code {
+        return None;
+    }
+    u64::from_str_radix(&groups.concat(), 16).ok()
+}
+
+fn fold_32(h: u64) -> u32 {
+    (h ^ (h >> 32)) as u32
+}
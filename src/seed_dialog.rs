@@ -0,0 +1,345 @@
+use std::fs;
+
+use bevy::prelude::*;
+use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
+use bevy::window::ReceivedCharacter;
+
+use crate::clipboard::paste_text;
+use crate::minigame::MinigameState;
+use crate::seed::Seed;
+use crate::terrain::{GenerationSeed, ResetTerrainEvent};
+use crate::*;
+
+const NORMAL_BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON_COLOR: Color = Color::rgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON_COLOR: Color = Color::rgb(0.35, 0.35, 0.35);
+
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum SeedDialogState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Component)]
+struct SeedDialog;
+#[derive(Component)]
+struct SeedInputText;
+#[derive(Component, Clone, Copy)]
+enum SeedDialogButton {
+    Reroll,
+    Apply,
+    Cancel,
+}
+
+/// What has been typed in the dialog so far
+#[derive(Resource, Default)]
+struct SeedInput(String);
+/// Clipboard being read for a paste, which can wait on the program that owns it
+#[derive(Resource, Default)]
+struct PasteTask(Option<Task<Option<String>>>);
+
+/// Tab opens a dialog to type a seed or a seed code, or to roll a random one. The chosen seed
+/// regenerates the world and is saved to the seed file for the next start.
+pub struct SeedDialogPlugin;
+
+impl Plugin for SeedDialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<SeedDialogState>()
+            .insert_resource(SeedInput::default())
+            .insert_resource(PasteTask::default())
+            .add_systems(
+                Update,
+                (
+                    toggle_seed_dialog,
+                    handle_seed_text_input,
+                    receive_pasted_text,
+                ),
+            )
+            .add_systems(OnEnter(SeedDialogState::Open), spawn_seed_dialog)
+            .add_systems(OnExit(SeedDialogState::Open), despawn_seed_dialog)
+            .add_systems(
+                Update,
+                (interact_with_seed_dialog_buttons, update_seed_input_text)
+                    .chain()
+                    .run_if(in_state(SeedDialogState::Open)),
+            );
+    }
+}
+
+fn toggle_seed_dialog(
+    keys: Res<Input<KeyCode>>,
+    dialog_state: Res<State<SeedDialogState>>,
+    minigame_state: Res<State<MinigameState>>,
+    mut next_state: ResMut<NextState<SeedDialogState>>,
+) {
+    match dialog_state.get() {
+        SeedDialogState::Closed => {
+            if keys.just_pressed(KeyCode::Tab) && *minigame_state.get() == MinigameState::None {
+                next_state.set(SeedDialogState::Open);
+            }
+        }
+        SeedDialogState::Open => {
+            if keys.any_just_pressed([KeyCode::Tab, KeyCode::Escape]) {
+                next_state.set(SeedDialogState::Closed);
+            }
+        }
+    }
+}
+
+fn spawn_seed_dialog(
+    mut commands: Commands,
+    seed: Res<GenerationSeed>,
+    mut input: ResMut<SeedInput>,
+) {
+    input.0.clear();
+
+    let text_style = |font_size: f32| TextStyle {
+        font_size,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    height: Val::Percent(100.0),
+                    width: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            SeedDialog,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(16.0),
+                        padding: UiRect::all(Val::Px(24.0)),
+                        ..default()
+                    },
+                    background_color: Color::GRAY.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("World seed", text_style(40.0)));
+                    parent.spawn(TextBundle::from_section(
+                        format!("Current: {}", seed.0),
+                        text_style(24.0),
+                    ));
+
+                    // Input box
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                height: Val::Px(56.0),
+                                width: Val::Px(480.0),
+                                padding: UiRect::horizontal(Val::Px(10.0)),
+                                border: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            border_color: Color::BLACK.into(),
+                            background_color: NORMAL_BUTTON_COLOR.into(),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                TextBundle::from_section("_", text_style(32.0)),
+                                SeedInputText,
+                            ));
+                        });
+
+                    parent.spawn(TextBundle::from_section(
+                        "Type or paste any text or a seed code, Enter to apply, Esc to cancel",
+                        text_style(18.0),
+                    ));
+
+                    // Buttons
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                column_gap: Val::Px(10.0),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for (button, label) in [
+                                (SeedDialogButton::Reroll, "Reroll"),
+                                (SeedDialogButton::Apply, "Apply"),
+                                (SeedDialogButton::Cancel, "Cancel"),
+                            ] {
+                                spawn_button(parent, button, label);
+                            }
+                        });
+                });
+        });
+}
+
+fn spawn_button(parent: &mut ChildBuilder, button: SeedDialogButton, label: &str) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    height: Val::Px(60.0),
+                    width: Val::Px(150.0),
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON_COLOR.into(),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 28.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_seed_dialog(mut commands: Commands, dialog_query: Query<Entity, With<SeedDialog>>) {
+    for entity in dialog_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn handle_seed_text_input(
+    keys: Res<Input<KeyCode>>,
+    dialog_state: Res<State<SeedDialogState>>,
+    mut char_reader: EventReader<ReceivedCharacter>,
+    mut input: ResMut<SeedInput>,
+    mut paste_task: ResMut<PasteTask>,
+    mut ev_reset: EventWriter<ResetTerrainEvent>,
+    mut next_state: ResMut<NextState<SeedDialogState>>,
+) {
+    // Keys typed while the dialog is closed are for the game
+    if *dialog_state.get() != SeedDialogState::Open {
+        char_reader.clear();
+        return;
+    }
+
+    for ev in char_reader.read() {
+        push_seed_chars(&mut input.0, [ev.char]);
+    }
+    // Ctrl+V, or Cmd+V on macOS
+    let modifier_held = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    if modifier_held && keys.just_pressed(KeyCode::V) && paste_task.0.is_none() {
+        paste_task.0 = Some(AsyncComputeTaskPool::get().spawn(async { paste_text() }));
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        input.0.pop();
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        apply_seed(&input.0, &mut ev_reset, &mut next_state);
+    }
+}
+
+fn receive_pasted_text(
+    dialog_state: Res<State<SeedDialogState>>,
+    mut paste_task: ResMut<PasteTask>,
+    mut input: ResMut<SeedInput>,
+) {
+    if !paste_task.0.as_ref().is_some_and(|task| task.is_finished()) {
+        return;
+    }
+    let Some(text) = paste_task.0.take().and_then(block_on) else {
+        warn!("Nothing to paste, the clipboard is empty or can't be read");
+        return;
+    };
+
+    // The dialog may have been closed while the clipboard was read
+    if *dialog_state.get() == SeedDialogState::Open {
+        push_seed_chars(&mut input.0, text.trim().chars());
+    }
+}
+
+/// Adds typed or pasted characters, control characters like line breaks are dropped
+fn push_seed_chars(input: &mut String, chars: impl IntoIterator<Item = char>) {
+    for c in chars {
+        if !c.is_control() && input.chars().count() < SEED_INPUT_MAX_LEN {
+            input.push(c);
+        }
+    }
+}
+
+fn interact_with_seed_dialog_buttons(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor, &SeedDialogButton),
+        Changed<Interaction>,
+    >,
+    mut input: ResMut<SeedInput>,
+    mut ev_reset: EventWriter<ResetTerrainEvent>,
+    mut next_state: ResMut<NextState<SeedDialogState>>,
+) {
+    for (interaction, mut background_color, button) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = PRESSED_BUTTON_COLOR.into();
+                match button {
+                    // The code of the new seed is shown, so it can be noted down before applying
+                    SeedDialogButton::Reroll => input.0 = Seed::random().to_string(),
+                    SeedDialogButton::Apply => apply_seed(&input.0, &mut ev_reset, &mut next_state),
+                    SeedDialogButton::Cancel => next_state.set(SeedDialogState::Closed),
+                }
+            }
+            Interaction::Hovered => {
+                *background_color = HOVERED_BUTTON_COLOR.into();
+            }
+            Interaction::None => {
+                *background_color = NORMAL_BUTTON_COLOR.into();
+            }
+        }
+    }
+}
+
+fn update_seed_input_text(
+    input: Res<SeedInput>,
+    mut text_query: Query<&mut Text, With<SeedInputText>>,
+) {
+    if !input.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!("{}_", input.0);
+    }
+}
+
+/// Regenerates the world from the typed seed and keeps it in the seed file
+fn apply_seed(
+    text: &str,
+    ev_reset: &mut EventWriter<ResetTerrainEvent>,
+    next_state: &mut NextState<SeedDialogState>,
+) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+
+    if let Err(err) = fs::write(SEED_FILE_PATH, text) {
+        error!("Failed to write seed file: {}", err);
+    }
+    ev_reset.send(ResetTerrainEvent(Some(Seed::from_text(text))));
+    next_state.set(SeedDialogState::Closed);
+}
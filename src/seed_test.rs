@@ -0,0 +1,60 @@
+#[cfg(test)]
+use std::collections::HashSet;
+
+#[cfg(test)]
+use crate::seed::*;
+
+#[test]
+fn test_seed_text_hash() {
+    // 64-bit FNV-1a
+    assert_eq!(Seed::from_text("").0, 0xcbf2_9ce4_8422_2325);
+    assert_eq!(Seed::from_text("a").0, 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(Seed::from_text("foobar").0, 0x8594_4171_f739_67e8);
+    assert_eq!(Seed::from_text("  foobar\n"), Seed::from_text("foobar"));
+
+    // Order and every byte count, not only their sum
+    let texts = [
+        "abc", "cba", "bac", "ab", "ba", "ac", "bb", "island", "islnad",
+    ];
+    let seeds: HashSet<Seed> = texts.iter().map(|text| Seed::from_text(text)).collect();
+    assert_eq!(seeds.len(), texts.len());
+}
+
+#[test]
+fn test_seed_code_roundtrip() {
+    let seed = Seed(0x1a2b_3c4d_5e6f_7a8b);
+    assert_eq!(seed.to_string(), "1A2B-3C4D-5E6F-7A8B");
+    assert_eq!(Seed(7).to_string(), "0000-0000-0000-0007");
+
+    for seed in [seed, Seed(0), Seed(u64::MAX), Seed::from_text("abc")] {
+        assert_eq!(Seed::from_text(&seed.to_string()), seed);
+        assert_eq!(Seed::from_text(&seed.to_string().to_lowercase()), seed);
+    }
+
+    // Anything that isn't a code is hashed like other text
+    for text in [
+        "1A2B-3C4D-5E6F",
+        "1A2B-3C4D-5E6F-7A8G",
+        "+A2B-3C4D-5E6F-7A8B",
+    ] {
+        assert_eq!(
+            Seed::from_text(text).0,
+            Seed::from_text(&format!(" {text} ")).0
+        );
+        assert_ne!(Seed::from_text(text), seed);
+    }
+}
+
+#[test]
+fn test_seed_streams() {
+    let seed = Seed::from_text("island");
+    assert_eq!(seed.terrain(), Seed::from_text("island").terrain());
+    assert_ne!(seed.terrain(), seed.sprites());
+    assert_ne!(seed.mazes(), seed.0);
+
+    // Neighbouring seeds don't give neighbouring streams
+    let (a, b) = (Seed(1), Seed(2));
+    assert_ne!(a.terrain(), b.terrain());
+    assert_ne!(a.sprites(), b.sprites());
+    assert!((a.mazes() ^ b.mazes()).count_ones() > 8);
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::rules::SettlementRules;
+use crate::utils::hash_2d;
+use crate::worldgen::GridRect;
+
+// Seed offset of the hash that jitters and picks village sites
+const SETTLEMENT_SEED: u32 = 1_299_709;
+
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// A village of houses along paths that cross at a well, inside a fence
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Settlement {
+    /// The same for a village every time the world is generated from the same seed
+    pub id: u64,
+    /// Lattice cell the village was picked from
+    pub site: (i32, i32),
+    pub centre: (i32, i32),
+    pub houses: Vec<((i32, i32), usize)>,
+    pub paths: Vec<(i32, i32)>,
+    pub fences: Vec<(i32, i32)>,
+}
+
+impl Settlement {
+    /// Every cell the village builds on, the well first
+    pub fn cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        std::iter::once(self.centre)
+            .chain(self.houses.iter().map(|(pos, _)| *pos))
+            .chain(self.paths.iter().copied())
+            .chain(self.fences.iter().copied())
+    }
+}
+
+/// Lattice cells whose village could reach into `rect`
+pub fn sites_in(rules: &SettlementRules, rect: GridRect) -> Vec<(i32, i32)> {
+    let spacing = rules.site_spacing.max(1);
+    let reach = rules.reach();
+    let (end_x, end_y) = rect.end();
+
+    let mut sites = Vec::new();
+    for i in (rect.x - reach).div_euclid(spacing)..=(end_x + reach).div_euclid(spacing) {
+        for j in (rect.y - reach).div_euclid(spacing)..=(end_y + reach).div_euclid(spacing) {
+            sites.push((i, j));
+        }
+    }
+    sites
+}
+
+/// Cells a village picked from lattice cell `site` can reach, `None` if the site has no village
+pub fn site_bounds(seed: u32, rules: &SettlementRules, site: (i32, i32)) -> Option<GridRect> {
+    let (_, (x, y)) = site_centre(seed, rules, site)?;
+    let reach = rules.reach();
+    Some(GridRect::new(
+        x - reach,
+        y - reach,
+        2 * reach as usize + 1,
+        2 * reach as usize + 1,
+    ))
+}
+
+/// The village picked from lattice cell `site`, if any.
+///
+/// Sites sit on a jittered lattice and a village never reaches past its own lattice cell.
+/// `is_site` says whether a village can be centred on a cell, `is_buildable` whether anything can
+/// be built on it, it is only asked about cells inside `site_bounds`.
+pub fn settlement_at(
+    seed: u32,
+    rules: &SettlementRules,
+    site: (i32, i32),
+    is_site: impl Fn((i32, i32)) -> bool,
+    is_buildable: impl Fn((i32, i32)) -> bool,
+) -> Option<Settlement> {
+    let (id, centre) = site_centre(seed, rules, site)?;
+    if !is_site(centre) {
+        return None;
+    }
+
+    // A village needs an open square around its well
+    let square = rules.radius / 2;
+    let is_open = (-square..=square)
+        .all(|dx| (-square..=square).all(|dy| is_buildable((centre.0 + dx, centre.1 + dy))));
+    if !is_open {
+        return None;
+    }
+
+    let settlement = lay_out(id, site, centre, rules, &is_buildable);
+    (settlement.houses.len() >= rules.min_houses).then_some(settlement)
+}
+
+/// Id and well of the village of `site`, `None` if the site isn't picked
+fn site_centre(
+    seed: u32,
+    rules: &SettlementRules,
+    (i, j): (i32, i32),
+) -> Option<(u64, (i32, i32))> {
+    let spacing = rules.site_spacing.max(1);
+    let reach = rules.reach();
+    let id = hash_2d(seed.wrapping_add(SETTLEMENT_SEED), i as i64, j as i64);
+    if (id & 0xffff) as f64 / 65536.0 >= rules.site_chance {
+        return None;
+    }
+
+    let jitter = (spacing - 2 * reach).max(1) as u64;
+    let centre = (
+        i * spacing + reach.min(spacing / 2) + ((id >> 16) % jitter) as i32,
+        j * spacing + reach.min(spacing / 2) + ((id >> 32) % jitter) as i32,
+    );
+    Some((id, centre))
+}
+
+/// Paths run from the well in the four directions, houses face the paths and a fence surrounds
+/// them with a gap where each path leaves
+fn lay_out(
+    id: u64,
+    site: (i32, i32),
+    centre: (i32, i32),
+    rules: &SettlementRules,
+    is_buildable: impl Fn((i32, i32)) -> bool,
+) -> Settlement {
+    let mut rng = StdRng::seed_from_u64(id);
+    let (cx, cy) = centre;
+
+    let mut taken = HashSet::from([centre]);
+    let mut paths = Vec::new();
+    for (dx, dy) in DIRECTIONS {
+        for d in 1..=rules.radius {
+            let pos = (cx + dx * d, cy + dy * d);
+            if !is_buildable(pos) {
+                break;
+            }
+            paths.push(pos);
+            taken.insert(pos);
+        }
+    }
+
+    let count = rng.gen_range(rules.min_houses..=rules.max_houses.max(rules.min_houses));
+    let mut houses = Vec::new();
+    for _ in 0..count * 8 {
+        if houses.len() == count {
+            break;
+        }
+
+        let (dx, dy) = DIRECTIONS[rng.gen_range(0..DIRECTIONS.len())];
+        let d = rng.gen_range(1..=rules.radius.max(1));
+        let side = if rng.gen_bool(0.5) { 1 } else { -1 };
+        // One step to the side of the path
+        let front = (cx + dx * d, cy + dy * d);
+        let pos = (front.0 + dy * side, front.1 + dx * side);
+        if taken.contains(&pos) || !paths.contains(&front) || !is_buildable(pos) {
+            continue;
+        }
+
+        let sprite = rules.house_sprites[rng.gen_range(0..rules.house_sprites.len())];
+        houses.push((pos, sprite));
+        taken.insert(pos);
+    }
+
+    let r = rules.radius + 1;
+    let mut fences = Vec::new();
+    for x in -r..=r {
+        for y in -r..=r {
+            let on_edge = x.abs() == r || y.abs() == r;
+            let is_gate = x == 0 || y == 0;
+            let pos = (cx + x, cy + y);
+            if on_edge && !is_gate && is_buildable(pos) {
+                fences.push(pos);
+            }
+        }
+    }
+
+    Settlement {
+        id,
+        site,
+        centre,
+        houses,
+        paths,
+        fences,
+    }
+}
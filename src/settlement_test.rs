@@ -0,0 +1,64 @@
+#[cfg(test)]
+use std::collections::{HashMap, HashSet};
+
+#[cfg(test)]
+use crate::{
+    coords::GridPos,
+    elevation::ElevationBand,
+    worldgen::*,
+    worldgen_test::{test_world, WORLD_RECT},
+};
+
+#[test]
+fn test_settlements_are_deterministic() {
+    let (generator, _) = test_world();
+    let settlements = generator.settlements_in(WORLD_RECT);
+    assert!(!settlements.is_empty());
+
+    let ids: HashSet<u64> = settlements.iter().map(|s| s.id).collect();
+    assert_eq!(ids.len(), settlements.len());
+
+    // Every chunk a village reaches into lays it out the same way
+    let mut chunks = HashMap::new();
+    for settlement in settlements.iter() {
+        for pos in settlement.cells() {
+            let region = chunks
+                .entry(GridPos::from(pos).chunk())
+                .or_insert_with_key(|chunk_pos| {
+                    generator.settlements_in(GridRect::from_chunk((*chunk_pos).into()))
+                });
+            assert!(region.contains(settlement));
+        }
+    }
+}
+
+#[test]
+fn test_settlement_layout() {
+    let (generator, region) = test_world();
+    let rules = &generator.rules().settlements;
+    assert!(!region.settlements.is_empty());
+
+    for settlement in region.settlements.iter() {
+        assert!(settlement.houses.len() >= rules.min_houses);
+        assert!(settlement.houses.len() <= rules.max_houses);
+
+        let cells: Vec<(i32, i32)> = settlement.cells().collect();
+        let unique: HashSet<(i32, i32)> = cells.iter().copied().collect();
+        assert_eq!(cells.len(), unique.len());
+
+        for pos in cells {
+            if !region.rect.contains(pos) {
+                continue;
+            }
+            assert!(region.ground.contains(&pos));
+            assert!(!region.rivers.contains(&pos));
+            assert_eq!(region.biome_at(pos).unwrap().band, ElevationBand::Lowland);
+        }
+        // Houses face a path
+        for ((x, y), _) in settlement.houses.iter() {
+            assert!([(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .any(|(dx, dy)| settlement.paths.contains(&(x + dx, y + dy))));
+        }
+    }
+}
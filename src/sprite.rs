@@ -5,9 +5,11 @@ use std::{
 };
 
 use bevy::prelude::Color;
-use noise::{NoiseFn, Perlin};
+use noise::NoiseFn;
 use rand::{rngs::StdRng, Rng};
 
+use crate::fractal::{FractalNoise, NoiseConfig, NoiseKind};
+use crate::seed::Seed;
 use crate::utils::seed_to_rng;
 
 const BIRTH_LIMIT: u32 = 5;
@@ -20,6 +22,7 @@ const SPRITE_WIDTH: usize = 45;
 const CELL_HEIGHT_PX: usize = 8;
 const CELL_WIDTH_PX: usize = 8;
 const PERLIN_SCALE: f64 = 320.5;
+const COLOR_NOISE: NoiseConfig = NoiseConfig::single(NoiseKind::Perlin, PERLIN_SCALE);
 
 #[derive(Debug)]
 pub enum Faction {
@@ -61,13 +64,13 @@ pub struct ComponentDrawer {
 }
 
 impl Component {
-    fn _draw(&self, board: &mut Vec<Vec<(f32, f32, f32, f32)>>) {
+    fn _draw(&self, board: &mut [Vec<(f32, f32, f32, f32)>]) {
         for c in self.cells.iter() {
             if c.position.0 < 0 && c.position.1 < 0 {
                 continue;
             }
             if let Some(row) = board.get(c.position.1 as usize) {
-                if let Some(_) = row.get(c.position.0 as usize) {
+                if row.get(c.position.0 as usize).is_some() {
                     board[c.position.1 as usize][c.position.0 as usize] = c.color;
                 }
             }
@@ -76,8 +79,8 @@ impl Component {
 }
 
 impl Sprite {
-    pub fn new(seed: u32) -> Self {
-        let cd = ComponentDrawer::new(seed, 45, 45);
+    pub fn new(seed: Seed) -> Self {
+        let cd = ComponentDrawer::new(seed.sprites(), 45, 45);
 
         Sprite {
             component_groups: group_components(cd.components),
@@ -88,7 +91,7 @@ impl Sprite {
         let start = SystemTime::now();
         let duration = start.duration_since(UNIX_EPOCH).unwrap();
         let unix_timestamp = duration.as_micros();
-        Self::new(unix_timestamp as u32)
+        Self::new(Seed(unix_timestamp as u64))
     }
 
     pub fn write_html_file(&self, html_file_path: &str) {
@@ -198,7 +201,7 @@ impl ComponentDrawer {
             }
         }
 
-        if sum_r >= sum_b && sum_r >= sum_b {
+        if sum_r >= sum_g && sum_r >= sum_b {
             return Color::RED;
         }
         if sum_g >= sum_r && sum_g >= sum_b {
@@ -236,75 +239,51 @@ impl ComponentDrawer {
         let avg_g = sum_g / count;
         let avg_b = sum_b / count;
 
-        if avg_r >= 0.0
-            && avg_r <= 127.0
-            && avg_g >= 0.0
-            && avg_g <= 127.0
-            && avg_b >= 0.0
-            && avg_b <= 127.0
+        if (0.0..=127.0).contains(&avg_r)
+            && (0.0..=127.0).contains(&avg_g)
+            && (0.0..=127.0).contains(&avg_b)
         {
             return Faction::ChaosWarriors;
         }
-        if avg_r >= 128.0
-            && avg_r <= 255.0
-            && avg_g >= 0.0
-            && avg_g <= 127.0
-            && avg_b >= 0.0
-            && avg_b <= 127.0
+        if (128.0..=255.0).contains(&avg_r)
+            && (0.0..=127.0).contains(&avg_g)
+            && (0.0..=127.0).contains(&avg_b)
         {
             return Faction::WaterBoys;
         }
-        if avg_r >= 0.0
-            && avg_r <= 127.0
-            && avg_g >= 128.0
-            && avg_g <= 255.0
-            && avg_b >= 0.0
-            && avg_b <= 127.0
+        if (0.0..=127.0).contains(&avg_r)
+            && (128.0..=255.0).contains(&avg_g)
+            && (0.0..=127.0).contains(&avg_b)
         {
             return Faction::ForestBoys;
         }
-        if avg_r >= 128.0
-            && avg_r <= 255.0
-            && avg_g >= 128.0
-            && avg_g <= 255.0
-            && avg_b >= 0.0
-            && avg_b <= 127.0
+        if (128.0..=255.0).contains(&avg_r)
+            && (128.0..=255.0).contains(&avg_g)
+            && (0.0..=127.0).contains(&avg_b)
         {
             return Faction::TechBoys;
         }
-        if avg_r >= 0.0
-            && avg_r <= 127.0
-            && avg_g >= 0.0
-            && avg_g <= 127.0
-            && avg_b >= 128.0
-            && avg_b <= 255.0
+        if (0.0..=127.0).contains(&avg_r)
+            && (0.0..=127.0).contains(&avg_g)
+            && (128.0..=255.0).contains(&avg_b)
         {
             return Faction::HellSpawn;
         }
-        if avg_r >= 128.0
-            && avg_r <= 255.0
-            && avg_g >= 0.0
-            && avg_g <= 127.0
-            && avg_b >= 128.0
-            && avg_b <= 255.0
+        if (128.0..=255.0).contains(&avg_r)
+            && (0.0..=127.0).contains(&avg_g)
+            && (128.0..=255.0).contains(&avg_b)
         {
             return Faction::SpaceAliens;
         }
-        if avg_r >= 0.0
-            && avg_r <= 127.0
-            && avg_g >= 128.0
-            && avg_g <= 255.0
-            && avg_b >= 128.0
-            && avg_b <= 255.0
+        if (0.0..=127.0).contains(&avg_r)
+            && (128.0..=255.0).contains(&avg_g)
+            && (128.0..=255.0).contains(&avg_b)
         {
             return Faction::GoldenBoys;
         }
-        if avg_r >= 128.0
-            && avg_r <= 255.0
-            && avg_g >= 128.0
-            && avg_g <= 255.0
-            && avg_b >= 128.0
-            && avg_b <= 255.0
+        if (128.0..=255.0).contains(&avg_r)
+            && (128.0..=255.0).contains(&avg_g)
+            && (128.0..=255.0).contains(&avg_b)
         {
             return Faction::JusticeSoldiers;
         }
@@ -326,7 +305,7 @@ impl ComponentDrawer {
         for i in (0..component_groups_len as i32).rev() {
             if let Some(component_group) = self.component_groups.get(i as usize) {
                 for component in component_group.components.iter() {
-                    if !(component.cells.len() as f32 >= largest as f32 * 0.25) {
+                    if (component.cells.len() as f32) < largest as f32 * 0.25 {
                         self.component_groups.remove(i as usize);
                         break;
                     }
@@ -334,7 +313,7 @@ impl ComponentDrawer {
                     let mut dupe_cells: Vec<Cell> = component.cells.clone();
 
                     for neg_component in self.neg_components.iter_mut() {
-                        if components_are_touching(&neg_component, &component) {
+                        if components_are_touching(neg_component, component) {
                             // Overlay neg_component cells ontop of component cells
                             dupe_cells.append(&mut neg_component.cells);
                         }
@@ -384,13 +363,11 @@ fn group_components(components: Vec<Component>) -> Vec<ComponentGroup> {
             components: vec![cp.clone()],
         };
 
-        'f: for j in 0..components.len() {
+        'f: for (j, cp2) in components.iter().enumerate() {
             if j == i || used_indeces.contains(&j) {
                 continue 'f;
             }
 
-            let cp2 = &components[j];
-
             // match mirrored components into component groups of 2
             for cell in cp.cells.iter() {
                 let (x, y) = cell.position;
@@ -426,12 +403,11 @@ pub fn make_rand_map(seed: u32, height: usize, width: usize) -> Vec<Vec<bool>> {
 
             // When close to center increase the cances to fill the map, so it's more likely to end up with a sprite that's connected in the middle
             let to_center = ((y as f32 - height as f32 * 0.5).abs() * 2.0) / height as f32;
-            if x as f32 == (width as f32 * 0.5).floor() - 1.0
-                || x as f32 == (width as f32 * 0.5) - 2.0
+            if (x as f32 == (width as f32 * 0.5).floor() - 1.0
+                || x as f32 == (width as f32 * 0.5) - 2.0)
+                && rand_range(&mut rng, 0.0, 0.4) > to_center
             {
-                if rand_range(&mut rng, 0.0, 0.4) > to_center {
-                    arr[y] = true;
-                }
+                arr[y] = true;
             }
         }
 
@@ -445,34 +421,34 @@ pub fn make_rand_map(seed: u32, height: usize, width: usize) -> Vec<Vec<bool>> {
 pub fn cellular_automata_do_steps(map: &mut Vec<Vec<bool>>) {
     let mut dupe = map.clone();
     for _ in 0..N_STEPS {
-        dupe = step(&mut dupe.clone());
+        dupe = step(&dupe);
     }
     *map = dupe;
 }
 
-fn step(map: &Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-    let mut dup = map.clone();
-    for x in 0..map.len() {
-        for y in 0..map[x].len() {
+fn step(map: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let mut dup = map.to_vec();
+    for (x, column) in dup.iter_mut().enumerate() {
+        let height = column.len();
+        for (y, cell) in column.iter_mut().enumerate() {
             // Ensure padding of 1 to prevent overflow when border is added later
-            if x == 0 || x == map.len() - 1 || y == 0 || y == map[x].len() - 1 {
-                dup[x][y] = false;
+            if x == 0 || x == map.len() - 1 || y == 0 || y == height - 1 {
+                *cell = false;
                 continue;
             }
 
-            let cell = dup[x][y];
             let n = get_neighbours(map, (x, y));
-            if cell && n < DEATH_LIMIT {
-                dup[x][y] = false;
-            } else if !cell && n > BIRTH_LIMIT {
-                dup[x][y] = true;
+            if *cell && n < DEATH_LIMIT {
+                *cell = false;
+            } else if !*cell && n > BIRTH_LIMIT {
+                *cell = true;
             }
         }
     }
     dup
 }
 
-fn get_neighbours(map: &Vec<Vec<bool>>, pos: (usize, usize)) -> u32 {
+fn get_neighbours(map: &[Vec<bool>], pos: (usize, usize)) -> u32 {
     let mut count = 0;
 
     for i in -1i32..2 {
@@ -495,22 +471,20 @@ fn get_neighbours(map: &Vec<Vec<bool>>, pos: (usize, usize)) -> u32 {
     count
 }
 
-fn get_at_pos(map: &Vec<Vec<bool>>, pos: (i32, i32)) -> Option<bool> {
+fn get_at_pos(map: &[Vec<bool>], pos: (i32, i32)) -> Option<bool> {
     if pos.0 < 0 || pos.1 < 0 {
         return None;
     }
 
-    if pos.0 < 0
-        || pos.0 >= map.len() as i32
-        || pos.1 < 0
-        || (pos.0 >= 0 && pos.1 >= map[pos.0 as usize].len() as i32)
-    {
+    if pos.0 >= map.len() as i32 || pos.1 >= map[pos.0 as usize].len() as i32 {
         return Some(false);
     }
 
     Some(map[pos.0 as usize][pos.1 as usize])
 }
 
+// 6.28318 is kept rather than TAU, the colours of every seed are tuned to it
+#[allow(clippy::approx_constant)]
 pub fn gen_colorscheme(seed: u32) -> Vec<(f32, f32, f32, f32)> {
     let mut rng = seed_to_rng(seed);
 
@@ -559,33 +533,33 @@ pub fn gen_colorscheme(seed: u32) -> Vec<(f32, f32, f32, f32)> {
     cols
 }
 
-pub fn fill_colors(seed: u32, map: &mut Vec<Vec<bool>>) -> (Vec<Component>, Vec<Component>) {
+pub fn fill_colors(seed: u32, map: &mut [Vec<bool>]) -> (Vec<Component>, Vec<Component>) {
     let colorscheme = gen_colorscheme(seed);
     let eye_colorscheme = gen_colorscheme(seed + 1);
 
-    let noise1 = Perlin::new(seed);
-    let noise2 = Perlin::new(seed + 1);
+    let noise1 = COLOR_NOISE.build(seed);
+    let noise2 = COLOR_NOISE.build(seed + 1);
 
     let components = flood_fill(
         map,
         colorscheme.clone(),
         eye_colorscheme.clone(),
         false,
-        noise1,
-        noise2,
+        &noise1,
+        &noise2,
     );
 
-    let neg_components = flood_fill_negative(map, colorscheme, eye_colorscheme, noise1, noise2);
+    let neg_components = flood_fill_negative(map, colorscheme, eye_colorscheme, &noise1, &noise2);
 
     (components, neg_components)
 }
 
 fn flood_fill_negative(
-    map: &mut Vec<Vec<bool>>,
+    map: &mut [Vec<bool>],
     colorscheme: Vec<(f32, f32, f32, f32)>,
     eye_colorscheme: Vec<(f32, f32, f32, f32)>,
-    noise1: Perlin,
-    noise2: Perlin,
+    noise1: &FractalNoise,
+    noise2: &FractalNoise,
 ) -> Vec<Component> {
     let mut negative_map = vec![];
     for x in 0..map.len() {
@@ -609,22 +583,15 @@ fn flood_fill_negative(
 }
 
 fn flood_fill(
-    map: &mut Vec<Vec<bool>>,
+    map: &mut [Vec<bool>],
     colorscheme: Vec<(f32, f32, f32, f32)>,
     eye_colorscheme: Vec<(f32, f32, f32, f32)>,
     is_neg_component: bool,
-    noise1: Perlin,
-    noise2: Perlin,
+    noise1: &FractalNoise,
+    noise2: &FractalNoise,
 ) -> Vec<Component> {
     let mut components: Vec<Component> = vec![];
-    let mut checked_map = vec![];
-    for x in 0..map.len() {
-        let mut arr = vec![];
-        for _y in 0..map[x].len() {
-            arr.push(false);
-        }
-        checked_map.push(arr)
-    }
+    let mut checked_map: Vec<Vec<bool>> = map.iter().map(|col| vec![false; col.len()]).collect();
 
     // bucket is all the cells that have been found through flood filling and whose neighbours will be checked next
     let mut bucket: Vec<(i32, i32)> = vec![];
@@ -640,7 +607,7 @@ fn flood_fill(
                     let mut valid = true;
 
                     // go through remaining cells in bucket
-                    while bucket.len() > 0 {
+                    while !bucket.is_empty() {
                         let pos: (i32, i32) = match bucket.pop() {
                             None => break,
                             Some(p) => p,
@@ -651,10 +618,10 @@ fn flood_fill(
                         let down = get_at_pos(map, (pos.0, pos.1 + 1));
                         let up = get_at_pos(map, (pos.0, pos.1 - 1));
                         // dont want negative groups that touch the edge of the sprite
-                        if is_neg_component {
-                            if left.is_none() || up.is_none() || down.is_none() || right.is_none() {
-                                valid = false;
-                            }
+                        if is_neg_component
+                            && (left.is_none() || up.is_none() || down.is_none() || right.is_none())
+                        {
+                            valid = false;
                         }
                         // also do a coloring step in this flood fill, speeds up processing a bit instead of doing it seperately
                         let col = choose_color(
@@ -688,7 +655,7 @@ fn flood_fill(
                         }
                         if left.is_some()
                             && left.unwrap()
-                            && pos.0 - 1 >= 0
+                            && pos.0 > 0
                             && pos.1 >= 0
                             && !checked_map[(pos.0 - 1) as usize][pos.1 as usize]
                         {
@@ -707,7 +674,7 @@ fn flood_fill(
                         if up.is_some()
                             && up.unwrap()
                             && pos.0 >= 0
-                            && pos.1 - 1 >= 0
+                            && pos.1 > 0
                             && !checked_map[pos.0 as usize][(pos.1 - 1) as usize]
                         {
                             bucket.push((pos.0, pos.1 - 1));
@@ -726,8 +693,9 @@ fn flood_fill(
     components
 }
 
+#[allow(clippy::too_many_arguments)]
 fn choose_color(
-    map: &mut Vec<Vec<bool>>,
+    map: &mut [Vec<bool>],
     pos: (i32, i32),
     is_neg_component: bool,
     right: Option<bool>,
@@ -737,18 +705,12 @@ fn choose_color(
     colorscheme: Vec<(f32, f32, f32, f32)>,
     eye_colorscheme: Vec<(f32, f32, f32, f32)>,
     component: &mut Component,
-    noise1: Perlin,
-    noise2: Perlin,
+    noise1: &FractalNoise,
+    noise2: &FractalNoise,
 ) -> (f32, f32, f32, f32) {
     let col_x = (pos.0 as f64 - (map.len() - 1) as f64 * 0.5).abs().ceil();
-    let mut n1 = (noise1.get([col_x / PERLIN_SCALE, pos.1 as f64 / PERLIN_SCALE]))
-        .abs()
-        .powf(1.5)
-        * 3.0;
-    let mut n2 = (noise2.get([col_x / PERLIN_SCALE, pos.1 as f64 / PERLIN_SCALE]))
-        .abs()
-        .powf(1.5)
-        * 3.0;
+    let mut n1 = (noise1.get([col_x, pos.1 as f64])).abs().powf(1.5) * 3.0;
+    let mut n2 = (noise2.get([col_x, pos.1 as f64])).abs().powf(1.5) * 3.0;
 
     // highlight colors based on amount of neighbours
     if down.is_none() || !down.unwrap() {
@@ -800,20 +762,11 @@ fn choose_color(
         });
     }
     // highlight colors if the difference in colors between neighbours is big
-    let c_0 = colorscheme
-        [noise1.get([col_x / PERLIN_SCALE, pos.1 as f64 / PERLIN_SCALE]) as usize * (N_COLORS - 1)];
-    let c_1 = colorscheme[noise1.get([col_x / PERLIN_SCALE, (pos.1 - 1) as f64 / PERLIN_SCALE])
-        as usize
-        * (N_COLORS - 1)];
-    let c_2 = colorscheme[noise1.get([col_x / PERLIN_SCALE, (pos.1 + 1) as f64 / PERLIN_SCALE])
-        as usize
-        * (N_COLORS - 1)];
-    let c_3 = colorscheme[noise1.get([(col_x - 1.0) / PERLIN_SCALE, pos.1 as f64 / PERLIN_SCALE])
-        as usize
-        * (N_COLORS - 1)];
-    let c_4 = colorscheme[noise1.get([(col_x + 1.0) / PERLIN_SCALE, pos.1 as f64 / PERLIN_SCALE])
-        as usize
-        * (N_COLORS - 1)];
+    let c_0 = colorscheme[noise1.get([col_x, pos.1 as f64]) as usize * (N_COLORS - 1)];
+    let c_1 = colorscheme[noise1.get([col_x, (pos.1 - 1) as f64]) as usize * (N_COLORS - 1)];
+    let c_2 = colorscheme[noise1.get([col_x, (pos.1 + 1) as f64]) as usize * (N_COLORS - 1)];
+    let c_3 = colorscheme[noise1.get([col_x - 1.0, pos.1 as f64]) as usize * (N_COLORS - 1)];
+    let c_4 = colorscheme[noise1.get([col_x + 1.0, pos.1 as f64]) as usize * (N_COLORS - 1)];
     let diff = ((c_0.0 - c_1.0).abs() + (c_0.1 - c_1.1).abs() + (c_0.2 - c_1.2).abs())
         + ((c_0.0 - c_2.0).abs() + (c_0.1 - c_2.1).abs() + (c_0.2 - c_2.2).abs())
         + ((c_0.0 - c_3.0).abs() + (c_0.1 - c_3.1).abs() + (c_0.2 - c_3.2).abs())
@@ -1,5 +1,8 @@
 #[cfg(test)]
-use crate::sprite::*;
+use bevy::prelude::Color;
+
+#[cfg(test)]
+use crate::{seed::Seed, sprite::*};
 
 #[test]
 fn test_gen_rand_sprite() {
@@ -9,6 +12,30 @@ fn test_gen_rand_sprite() {
 
 #[test]
 fn test_gen_same_sprite() {
-    let sprite = Sprite::new(1234);
+    let sprite = Sprite::new(Seed(1234));
     sprite.write_html_file("./sprite.html");
 }
+
+#[test]
+fn test_primary_color_is_largest_channel() {
+    let drawer = |color| ComponentDrawer {
+        components: vec![Component {
+            cells: vec![Cell {
+                position: (0, 0),
+                color,
+            }],
+        }],
+        ..Default::default()
+    };
+
+    // Red above blue but below green is still green
+    assert_eq!(
+        drawer((0.5, 0.9, 0.1, 1.0)).get_primary_color(),
+        Color::GREEN
+    );
+    assert_eq!(drawer((0.9, 0.5, 0.1, 1.0)).get_primary_color(), Color::RED);
+    assert_eq!(
+        drawer((0.1, 0.5, 0.9, 1.0)).get_primary_color(),
+        Color::BLUE
+    );
+}
@@ -1,138 +1,307 @@
+use std::sync::Arc;
 use std::time::Duration;
 
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics, RegisterDiagnostic};
 use bevy::math::{vec2, vec3};
 use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
 use bevy::time::common_conditions::on_timer;
-use bevy::utils::hashbrown::HashSet;
-use bevy::utils::HashMap;
-use noise::{NoiseFn, Perlin};
-use rand::Rng;
+use bevy::utils::BoxedFuture;
+use bevy::utils::{HashMap, HashSet};
 
+use crate::chunk_tiles::ChunkTiles;
+use crate::coords::{ChunkPos, GridPos, WorldPos};
+use crate::lod::bake_lod_pixels;
 use crate::player::{CurrentPlayerChunkPos, PlayerChunkUpdateEvent};
-use crate::utils::*;
+use crate::rules::TerrainRules;
+use crate::seed::Seed;
+use crate::tilemap::{build_layer_meshes, is_interactive};
+use crate::worldgen::{Region, Tile, WorldGenerator};
 use crate::*;
 
 #[derive(Component)]
 pub struct TileComponent;
 #[derive(Component)]
-pub struct PUID(pub u32);
+pub struct PUID(pub u64);
+/// Ground, beach and water of every loaded chunk
+#[derive(Resource, Default)]
+pub struct TerrainTiles(pub HashMap<ChunkPos, ChunkTiles>);
+/// Entities of every loaded chunk. Chunks past `load_radius` are only spawned as their map image,
+/// their full tiles are built once they come back in range. Every chunk has a map image, so
+/// zooming only switches which of the two is shown.
+#[derive(Resource, Default)]
+struct CurrentChunks {
+    entities: HashMap<ChunkPos, Vec<Entity>>,
+    detailed: HashSet<ChunkPos>,
+    mapped: HashSet<ChunkPos>,
+}
+#[derive(Resource)]
+pub struct GenerationSeed(pub Seed);
+#[derive(Resource)]
+pub struct GenerationRules(pub Arc<TerrainRules>);
+/// How far around the player chunks are kept loaded, in chunks
+#[derive(Resource, Clone, Debug)]
+pub struct ChunkStreamingSettings {
+    pub load_radius: u32,
+    /// Chunks are only unloaded past this radius, so that walking back and forth over a border
+    /// doesn't reload them. Should be at least `load_radius + preload_ahead`
+    pub unload_radius: u32,
+    /// Extra chunks loaded ahead of the player in the direction they are moving
+    pub preload_ahead: u32,
+    /// How far chunks are loaded when zoomed out, past `load_radius` they are drawn as a map
+    pub lod_radius: u32,
+    /// Camera scale above which every chunk is drawn as a map
+    pub lod_zoom: f32,
+}
+/// Whether the camera is zoomed out far enough to draw the world as a map
+#[derive(Resource, Default)]
+pub struct TerrainLod {
+    pub zoomed_out: bool,
+}
+// Full resolution tiles of a chunk, and the map image standing in for them
+#[derive(Component)]
+struct ChunkDetail(ChunkPos);
+#[derive(Component)]
+struct ChunkLod(ChunkPos);
+/// Chunks being generated, and whether their full tiles are wanted or only their map image
 #[derive(Resource)]
-pub struct GroundTiles(pub HashSet<(i32, i32)>);
+struct ChunkTasks(HashMap<ChunkPos, (Task<Region>, bool)>);
 #[derive(Resource)]
-struct CurrentChunks(HashMap<(i32, i32), Vec<Entity>>);
+struct TerrainAtlas {
+    atlas: Handle<TextureAtlas>,
+    material: Handle<ColorMaterial>,
+}
 #[derive(Resource)]
-pub struct GenerationSeed(pub u32);
+struct TerrainRulesHandle(Handle<TerrainRulesAsset>);
+/// Regenerates the world from a new seed, a random one if `None`
 #[derive(Event)]
-pub struct ResetTerrainEvent;
-
-#[derive(Component, Eq, PartialEq, Hash)]
-pub struct Tile {
-    pub puid: u32,
-    pub pos: (i32, i32),
-    pub sprite: usize,
-    pub z_index: i32,
+pub struct ResetTerrainEvent(pub Option<Seed>);
+/// Sent whenever entities are spawned for a chunk, with the entities spawned that time. A chunk's
+/// full tiles and its map image can be spawned at different times, so the same `chunk_pos` is sent
+/// again when the player walks up to a chunk only drawn as a map, or zooms out over one only drawn
+/// in full. `ChunkUnloaded` is sent once, with all of them.
+#[derive(Event, Clone, Debug)]
+pub struct ChunkLoaded {
+    pub chunk_pos: ChunkPos,
+    pub entities: Vec<Entity>,
 }
+/// Sent when the tiles of a chunk are despawned, the entities are gone by the next frame
+#[derive(Event, Clone, Debug)]
+pub struct ChunkUnloaded {
+    pub chunk_pos: ChunkPos,
+    pub entities: Vec<Entity>,
+}
+
+#[derive(Asset, TypePath)]
+pub struct TerrainRulesAsset(pub TerrainRules);
+
+#[derive(Default)]
+struct TerrainRulesLoader;
 
 pub struct TerrainPlugin;
 
+pub const TERRAIN_TILES_BYTES: DiagnosticId =
+    DiagnosticId::from_u128(0x6c1b_52e0_9f3a_4d8e_b7a1_30c4_e2f9_5d17);
+pub const LOADED_CHUNKS: DiagnosticId =
+    DiagnosticId::from_u128(0x2f94_d6a3_18c7_4b05_a9e2_7d13_c6b8_0e41);
+
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(GroundTiles(HashSet::new()))
-            .insert_resource(CurrentChunks(HashMap::new()))
+        app.insert_resource(TerrainTiles::default())
+            .insert_resource(CurrentChunks::default())
+            .insert_resource(ChunkTasks(HashMap::new()))
+            .insert_resource(ChunkStreamingSettings::default())
+            .insert_resource(TerrainLod::default())
+            .insert_resource(GenerationRules(Arc::new(TerrainRules::default())))
+            .init_asset::<TerrainRulesAsset>()
+            .init_asset_loader::<TerrainRulesLoader>()
+            .add_systems(Startup, load_terrain_rules)
+            .add_systems(Startup, load_terrain_atlas)
             .add_systems(Update, handle_terrain_reset_event)
+            .add_systems(Update, handle_terrain_rules_change)
             .add_systems(Update, despawn_chunks)
             .add_systems(
                 Update,
-                clean_ground_tiles.run_if(on_timer(Duration::from_secs_f32(2.0))),
+                measure_terrain_tiles.run_if(on_timer(Duration::from_secs_f32(1.0))),
             )
             .add_systems(Update, handle_player_chunk_update_event)
-            .add_event::<ResetTerrainEvent>();
+            .add_systems(Update, spawn_generated_chunks)
+            .add_systems(Update, update_terrain_lod)
+            .add_event::<ResetTerrainEvent>()
+            .add_event::<ChunkLoaded>()
+            .add_event::<ChunkUnloaded>()
+            .register_diagnostic(
+                Diagnostic::new(TERRAIN_TILES_BYTES, "terrain_tiles_bytes", 20).with_suffix(" B"),
+            )
+            .register_diagnostic(Diagnostic::new(LOADED_CHUNKS, "loaded_chunks", 20));
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_terrain_reset_event(
     mut commands: Commands,
     mut reader: EventReader<ResetTerrainEvent>,
     mut ev_writer: EventWriter<PlayerChunkUpdateEvent>,
     player_pos: Res<CurrentPlayerChunkPos>,
     mut chunks: ResMut<CurrentChunks>,
-    mut ground_tiles: ResMut<GroundTiles>,
+    mut chunk_tasks: ResMut<ChunkTasks>,
+    mut terrain_tiles: ResMut<TerrainTiles>,
     mut seed: ResMut<GenerationSeed>,
-    tile_q: Query<Entity, With<TileComponent>>,
+    mut ev_unloaded: EventWriter<ChunkUnloaded>,
 ) {
-    if reader.is_empty() {
+    let Some(new_seed) = reader.read().last().map(|ev| ev.0) else {
         return;
-    }
-
-    reader.clear();
-    for t in tile_q.iter() {
-        commands.entity(t).despawn();
-    }
+    };
 
-    // Reset res
-    chunks.0.clear();
-    ground_tiles.0.clear();
+    clear_terrain(
+        &mut commands,
+        &mut chunks,
+        &mut chunk_tasks,
+        &mut terrain_tiles,
+        &mut ev_unloaded,
+    );
 
-    let mut rng = rand::thread_rng();
-    let new_seed_str = rng.gen::<u32>().to_string();
-    println!("Seed set to : {}", new_seed_str);
-    let new_seed = seed_from_seed_str(new_seed_str);
-    seed.0 = new_seed;
+    seed.0 = new_seed.unwrap_or_else(Seed::random);
+    info!("Seed set to: {}", seed.0);
 
     // Trigger world re-generation
-    let (x, y) = player_pos.0;
-    ev_writer.send(PlayerChunkUpdateEvent((x, y)));
+    ev_writer.send(PlayerChunkUpdateEvent(player_pos.0));
+}
+
+fn load_terrain_rules(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handle = asset_server.load(TERRAIN_RULES_PATH);
+    commands.insert_resource(TerrainRulesHandle(handle));
 }
 
-fn clean_ground_tiles(
+#[allow(clippy::too_many_arguments)]
+fn handle_terrain_rules_change(
+    mut commands: Commands,
+    mut reader: EventReader<AssetEvent<TerrainRulesAsset>>,
+    mut ev_writer: EventWriter<PlayerChunkUpdateEvent>,
     player_pos: Res<CurrentPlayerChunkPos>,
-    mut ground_tiles: ResMut<GroundTiles>,
+    rules_assets: Res<Assets<TerrainRulesAsset>>,
+    rules_handle: Res<TerrainRulesHandle>,
+    mut rules: ResMut<GenerationRules>,
+    mut chunks: ResMut<CurrentChunks>,
+    mut chunk_tasks: ResMut<ChunkTasks>,
+    mut terrain_tiles: ResMut<TerrainTiles>,
+    mut ev_unloaded: EventWriter<ChunkUnloaded>,
+) {
+    for ev in reader.read() {
+        let id = match ev {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => id,
+            _ => continue,
+        };
+        if *id != rules_handle.0.id() {
+            continue;
+        }
+        let Some(asset) = rules_assets.get(*id) else {
+            continue;
+        };
+        if *rules.0 == asset.0 {
+            continue;
+        }
+
+        info!("Terrain rules reloaded");
+        rules.0 = Arc::new(asset.0.clone());
+        clear_terrain(
+            &mut commands,
+            &mut chunks,
+            &mut chunk_tasks,
+            &mut terrain_tiles,
+            &mut ev_unloaded,
+        );
+
+        // Trigger world re-generation
+        ev_writer.send(PlayerChunkUpdateEvent(player_pos.0));
+    }
+}
+
+fn clear_terrain(
+    commands: &mut Commands,
+    chunks: &mut CurrentChunks,
+    chunk_tasks: &mut ChunkTasks,
+    terrain_tiles: &mut TerrainTiles,
+    ev_unloaded: &mut EventWriter<ChunkUnloaded>,
 ) {
-    let (x, y) = player_pos.0;
-    ground_tiles.0.retain(|pos| {
-        let (px, py) = grid_to_chunk(pos.0 as f32, pos.1 as f32);
-        px.abs_diff(x) <= 1 || py.abs_diff(y) <= 1
+    chunks.detailed.clear();
+    chunks.mapped.clear();
+    for (chunk_pos, entities) in chunks.entities.drain() {
+        for e in entities.iter() {
+            commands.entity(*e).despawn();
+        }
+        ev_unloaded.send(ChunkUnloaded {
+            chunk_pos,
+            entities,
+        });
+    }
+
+    // Reset res
+    // Chunks still generating were made with the old seed or rules
+    chunk_tasks.0.clear();
+    terrain_tiles.0.clear();
+}
+
+fn measure_terrain_tiles(mut diagnostics: Diagnostics, terrain_tiles: Res<TerrainTiles>) {
+    diagnostics.add_measurement(TERRAIN_TILES_BYTES, || {
+        terrain_tiles
+            .0
+            .values()
+            .map(|t| t.size_in_bytes())
+            .sum::<usize>() as f64
     });
+    diagnostics.add_measurement(LOADED_CHUNKS, || terrain_tiles.0.len() as f64);
 }
 
 fn despawn_chunks(
     mut commands: Commands,
     mut current_chunks: ResMut<CurrentChunks>,
+    mut terrain_tiles: ResMut<TerrainTiles>,
     player_pos: Res<CurrentPlayerChunkPos>,
+    settings: Res<ChunkStreamingSettings>,
+    lod: Res<TerrainLod>,
+    mut ev_unloaded: EventWriter<ChunkUnloaded>,
 ) {
     let mut keys_to_remove = Vec::new();
-    let (x, y) = player_pos.0;
+    let unload_radius = settings.unload_radius_for(lod.zoomed_out);
 
-    for ((cx, cy), entities) in current_chunks.0.iter() {
-        if cx.abs_diff(x) <= 1 && cy.abs_diff(y) <= 1 {
+    for (chunk_pos, entities) in current_chunks.entities.iter() {
+        if chunk_pos.distance(player_pos.0) <= unload_radius {
             continue;
         }
 
         for e in entities.iter() {
             commands.entity(*e).despawn();
         }
-        keys_to_remove.push((*cx, *cy));
+        keys_to_remove.push(*chunk_pos);
     }
 
-    for (cx, cy) in keys_to_remove {
-        current_chunks.0.remove(&(cx, cy));
+    for chunk_pos in keys_to_remove {
+        // Ground data lives exactly as long as the chunk's tiles
+        terrain_tiles.0.remove(&chunk_pos);
+        current_chunks.detailed.remove(&chunk_pos);
+        current_chunks.mapped.remove(&chunk_pos);
+        if let Some(entities) = current_chunks.entities.remove(&chunk_pos) {
+            ev_unloaded.send(ChunkUnloaded {
+                chunk_pos,
+                entities,
+            });
+        }
     }
 }
 
-fn handle_player_chunk_update_event(
+fn load_terrain_atlas(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    seed: Res<GenerationSeed>,
-    mut current_chunks: ResMut<CurrentChunks>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut ev_chunk_update: EventReader<PlayerChunkUpdateEvent>,
-    mut ground_tiles: ResMut<GroundTiles>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    if ev_chunk_update.is_empty() {
-        return;
-    }
-
-    let texture_handle = asset_server.load(SPRITE_SHEET_PATH);
+    let texture_handle: Handle<Image> = asset_server.load(SPRITE_SHEET_PATH);
+    let material = materials.add(ColorMaterial::from(texture_handle.clone()));
     let texture_atlas = TextureAtlas::from_grid(
         texture_handle,
         vec2(TILE_W as f32, TILE_H as f32),
@@ -141,220 +310,358 @@ fn handle_player_chunk_update_event(
         Some(Vec2::splat(SPRITE_PADDING)),
         Some(Vec2::splat(SPRITE_SHEET_OFFSET)),
     );
-    let handle = texture_atlases.add(texture_atlas);
+    commands.insert_resource(TerrainAtlas {
+        atlas: texture_atlases.add(texture_atlas),
+        material,
+    });
+}
 
-    for new_chunk_pos in ev_chunk_update.read() {
-        let (x, y) = new_chunk_pos.0;
-
-        let chunk_nei = [
-            (-1, 0),
-            (1, 0),
-            (0, -1),
-            (0, 1),
-            (-1, 1),
-            (1, 1),
-            (-1, -1),
-            (1, -1),
-            (0, 0),
-        ];
-        let mut tiles = HashSet::new();
-        let mut ground_map = HashSet::new();
-
-        for (i, j) in chunk_nei.iter() {
-            let (x, y) = (x + *i, y + *j);
-            if current_chunks.0.contains_key(&(x, y)) {
-                continue;
-            }
+#[allow(clippy::too_many_arguments)]
+fn handle_player_chunk_update_event(
+    seed: Res<GenerationSeed>,
+    rules: Res<GenerationRules>,
+    settings: Res<ChunkStreamingSettings>,
+    lod: Res<TerrainLod>,
+    current_chunks: Res<CurrentChunks>,
+    mut chunk_tasks: ResMut<ChunkTasks>,
+    mut ev_chunk_update: EventReader<PlayerChunkUpdateEvent>,
+    mut last_chunk_pos: Local<Option<ChunkPos>>,
+    mut generator: Local<Option<WorldGenerator>>,
+) {
+    if ev_chunk_update.is_empty() {
+        return;
+    }
 
-            let start = (x * CHUNK_W as i32, y * CHUNK_H as i32);
-            let (chunk_tiles, chunk_ground_map) = gen_chunk(seed.0, (start.0, start.1));
-            tiles.extend(chunk_tiles);
-            ground_map.extend(chunk_ground_map);
-        }
+    let pool = AsyncComputeTaskPool::get();
+    // Kept between events so that chunks share the villages and roads laid out for their
+    // neighbours, rebuilt when the seed or rules change
+    let is_current = generator.as_ref().is_some_and(|generator| {
+        generator.seed() == seed.0.terrain() && std::ptr::eq(generator.rules(), &*rules.0)
+    });
+    if !is_current {
+        *generator = Some(WorldGenerator::with_rules(
+            seed.0.terrain(),
+            rules.0.clone(),
+        ));
+    }
+    let generator = generator.as_ref().unwrap();
 
-        let mut updated_ground_map = HashSet::new();
-        for (x, y) in ground_map.iter() {
-            let (num_nei, tile) = process_tile((*x, *y), &ground_map);
-            if num_nei == 1 {
+    for new_chunk_pos in ev_chunk_update.read() {
+        let center = new_chunk_pos.0;
+        let direction = match *last_chunk_pos {
+            Some(last) => ((center.x - last.x).signum(), (center.y - last.y).signum()),
+            None => (0, 0),
+        };
+        *last_chunk_pos = Some(center);
+
+        // Dropping a task cancels it, so chunks the player moved away from stop generating
+        let unload_radius = settings.unload_radius_for(lod.zoomed_out);
+        chunk_tasks
+            .0
+            .retain(|chunk_pos, _| chunk_pos.distance(center) <= unload_radius);
+
+        // Chunks loaded when zoomed in get their full tiles, the rest only a map image
+        let near: HashSet<ChunkPos> = settings
+            .chunks_to_load(center, direction, false)
+            .into_iter()
+            .collect();
+
+        for chunk_pos in settings.chunks_to_load(center, direction, lod.zoomed_out) {
+            let detail = near.contains(&chunk_pos);
+            if let Some((_, wants_detail)) = chunk_tasks.0.get_mut(&chunk_pos) {
+                *wants_detail |= detail;
                 continue;
             }
 
-            // Ignore edges
-            // This will help in better player visualization when going from land to water
-            updated_ground_map.insert((*x, *y));
-            tiles.insert(Tile::new((*x, *y), tile, 0, seed.0));
-        }
-        ground_tiles.0.extend(updated_ground_map);
-
-        for t in tiles.iter() {
-            let (cx, cy) = grid_to_chunk(t.pos.0 as f32, t.pos.1 as f32);
-            let (x, y) = grid_to_world(t.pos.0 as f32, t.pos.1 as f32);
-            let (x, y) = center_to_top_left(x, y);
-
-            let sprite_sheet_bundle = SpriteSheetBundle {
-                texture_atlas: handle.clone(),
-                sprite: TextureAtlasSprite::new(t.sprite),
-                transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR as f32))
-                    .with_translation(vec3(x, y, t.z_index as f32)),
-                ..default()
-            };
-
-            let e = if t.z_index <= 0 {
-                commands.spawn((sprite_sheet_bundle, TileComponent)).id()
+            // A chunk only drawn as a map is generated again for its full tiles
+            let is_loaded = if detail {
+                current_chunks.detailed.contains(&chunk_pos)
             } else {
-                commands
-                    .spawn((sprite_sheet_bundle, TileComponent, t.puid_component()))
-                    .id()
+                current_chunks.mapped.contains(&chunk_pos)
             };
+            if is_loaded {
+                continue;
+            }
 
-            current_chunks
-                .0
-                .entry((cx, cy))
-                .or_insert_with(Vec::new)
-                .push(e);
+            let generator = generator.clone();
+            let task = pool.spawn(async move { generator.generate_chunk(chunk_pos.into()) });
+            chunk_tasks.0.insert(chunk_pos, (task, detail));
         }
     }
 }
 
-fn gen_chunk(gen_seed: u32, start: (i32, i32)) -> (HashSet<Tile>, HashSet<(i32, i32)>) {
-    let mut rng = seed_to_rng(gen_seed);
-    let noise = Perlin::new(gen_seed);
-
-    let mut tiles = HashSet::new();
-    let mut ground_map = HashSet::new();
-    let end = (start.0 + CHUNK_W as i32, start.1 + CHUNK_H as i32);
-    for x in start.0 - 1..end.0 + 1 {
-        for y in start.1 - 1..end.1 + 1 {
-            let noise_val1 = noise.get([x as f64 / 100.5, y as f64 / 100.5]);
-            let noise_val2 = noise.get([x as f64 / 53.5, y as f64 / 53.5]);
-            let noise_val3 = noise.get([x as f64 / 43.5, y as f64 / 43.5]);
-            let noise_val4 = noise.get([x as f64 / 23.5, y as f64 / 23.5]);
-            let noise_val = (noise_val1 + noise_val2 + noise_val3 + noise_val4) / 4.0;
-            let chance = rng.gen_range(0.0..1.0);
-
-            // Ground
-            if noise_val > 0.0 {
-                ground_map.insert((x, y));
-            } else {
-                continue;
-            }
+#[allow(clippy::too_many_arguments)]
+fn spawn_generated_chunks(
+    mut commands: Commands,
+    atlas: Res<TerrainAtlas>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut chunk_tasks: ResMut<ChunkTasks>,
+    mut current_chunks: ResMut<CurrentChunks>,
+    mut terrain_tiles: ResMut<TerrainTiles>,
+    settings: Res<ChunkStreamingSettings>,
+    player_pos: Res<CurrentPlayerChunkPos>,
+    mut ev_loaded: EventWriter<ChunkLoaded>,
+) {
+    // The meshes need the size of the sprite sheet, finished chunks wait until it is loaded
+    let Some(texture_atlas) = texture_atlases.get(&atlas.atlas) else {
+        return;
+    };
+    let Some(image_size) = images.get(&texture_atlas.texture).map(|i| i.size_f32()) else {
+        return;
+    };
 
-            // Too close to shore
-            if noise_val < 0.05 {
-                continue;
-            }
+    // Building the meshes takes a while, so chunks are spread over frames, nearest first
+    let mut finished: Vec<ChunkPos> = chunk_tasks
+        .0
+        .iter()
+        .filter(|(_, (task, _))| task.is_finished())
+        .map(|(chunk_pos, _)| *chunk_pos)
+        .collect();
+    finished.sort_by_key(|chunk_pos| chunk_pos.distance(player_pos.0));
+    finished.truncate(CHUNK_SPAWNS_PER_FRAME);
+
+    for chunk_pos in finished {
+        let Some((task, wants_detail)) = chunk_tasks.0.remove(&chunk_pos) else {
+            continue;
+        };
+        let region = block_on(task);
+
+        // Far chunks only need their map image, unless the player came close in the meantime
+        let is_near = chunk_pos.distance(player_pos.0) <= settings.load_radius;
+        let needs_lod = !current_chunks.mapped.contains(&chunk_pos);
+        let needs_detail =
+            !current_chunks.detailed.contains(&chunk_pos) && (wants_detail || is_near);
+        if !needs_lod && !needs_detail {
+            continue;
+        }
 
-            // Dense Forest
-            if (noise_val > 0.5 || noise_val3 > 0.98) && chance > 0.2 {
-                tiles.insert(Tile::new((x, y), 27, 5, gen_seed));
-                continue;
-            }
-            // Patch Forest
-            if noise_val3 > 0.5 && noise_val < 0.5 && chance > 0.4 {
-                let chance2 = rng.gen_range(0.0..1.0);
-                let tile = if chance2 > 0.7 {
-                    rng.gen_range(24..=26)
-                } else {
-                    rng.gen_range(24..=25)
-                };
-                tiles.insert(Tile::new((x, y), tile, 3, gen_seed));
-                continue;
-            }
-            // Sparse Forest
-            if noise_val4 > 0.4 && noise_val < 0.5 && noise_val3 < 0.5 && chance > 0.9 {
-                let chance = rng.gen_range(0.0..1.0);
-                let tile = if chance > 0.78 {
-                    rng.gen_range(28..=29)
-                } else {
-                    rng.gen_range(24..=25)
-                };
-                tiles.insert(Tile::new((x, y), tile, 3, gen_seed));
-                continue;
+        // A chunk already loaded as a map keeps its tiles
+        terrain_tiles
+            .0
+            .entry(chunk_pos)
+            .or_insert_with(|| ChunkTiles::from_region(chunk_pos.into(), &region));
+
+        let mut entities = Vec::new();
+        if needs_detail {
+            let (interactive, batched): (Vec<&Tile>, Vec<&Tile>) =
+                region.tiles.iter().partition(|t| is_interactive(t));
+
+            // Every static layer of the chunk is a single mesh
+            for (z_index, mesh) in build_layer_meshes(batched, texture_atlas, image_size) {
+                let e = commands
+                    .spawn((
+                        MaterialMesh2dBundle {
+                            mesh: meshes.add(mesh).into(),
+                            material: atlas.material.clone(),
+                            transform: Transform::from_xyz(0.0, 0.0, z_index as f32),
+                            ..default()
+                        },
+                        TileComponent,
+                        ChunkDetail(chunk_pos),
+                    ))
+                    .id();
+                entities.push(e);
             }
 
-            // Bones
-            if noise_val > 0.3 && noise_val < 0.5 && noise_val3 < 0.5 && chance > 0.98 {
-                let tile = rng.gen_range(40..=43);
-                tiles.insert(Tile::new((x, y), tile, 1, gen_seed));
-                continue;
-            }
+            for t in interactive {
+                let WorldPos { x, y } = GridPos::from(t.pos).world();
 
-            // Settlements
-            if noise_val > 0.1 && noise_val < 0.3 && noise_val3 < 0.4 && chance > 0.8 {
-                let chance2 = rng.gen_range(0.0..1.0);
-
-                if chance2 > 0.98 {
-                    let chance3 = rng.gen_range(0.0..1.0);
-                    let tile = if chance3 > 0.75 {
-                        rng.gen_range(18..=19)
-                    } else {
-                        rng.gen_range(16..=17)
-                    };
-                    tiles.insert(Tile::new((x, y), tile, 8, gen_seed));
-                } else {
-                    if noise_val > 0.2 && noise_val < 0.3 && noise_val3 < 0.3 && chance > 0.9 {
-                        tiles.insert(Tile::new((x, y), 32, 1, gen_seed));
-                    }
-                }
+                let sprite_sheet_bundle = SpriteSheetBundle {
+                    texture_atlas: atlas.atlas.clone(),
+                    sprite: TextureAtlasSprite::new(t.sprite),
+                    transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR as f32))
+                        .with_translation(vec3(x, y, t.z_index as f32)),
+                    ..default()
+                };
 
-                continue;
+                let e = commands
+                    .spawn((
+                        sprite_sheet_bundle,
+                        TileComponent,
+                        ChunkDetail(chunk_pos),
+                        t.puid_component(),
+                    ))
+                    .id();
+                entities.push(e);
             }
+            current_chunks.detailed.insert(chunk_pos);
+        }
 
-            // Color Check
-            // if noise_val > 0.1 && noise_val4 < 0.5 {
-            //     tiles.insert(Tile::new((x, y), 64, 1));
-            //     continue;
-            // }
+        if needs_lod {
+            // One pixel per cell, under the full tiles
+            let lod_image = Image::new(
+                Extent3d {
+                    width: region.rect.w as u32,
+                    height: region.rect.h as u32,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                bake_lod_pixels(&region),
+                TextureFormat::Rgba8UnormSrgb,
+            );
+            let first = GridPos::new(region.rect.x, region.rect.y).world();
+            let last = GridPos::new(region.rect.end().0 - 1, region.rect.end().1 - 1).world();
+            let lod_size = vec2(
+                (region.rect.w * TILE_W * SPRITE_SCALE_FACTOR) as f32,
+                (region.rect.h * TILE_H * SPRITE_SCALE_FACTOR) as f32,
+            );
+
+            let e = commands
+                .spawn((
+                    SpriteBundle {
+                        texture: images.add(lod_image),
+                        sprite: Sprite {
+                            custom_size: Some(lod_size),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(
+                            (first.x + last.x) / 2.0,
+                            (first.y + last.y) / 2.0,
+                            LOD_Z_INDEX,
+                        ),
+                        ..default()
+                    },
+                    TileComponent,
+                    ChunkLod(chunk_pos),
+                ))
+                .id();
+            entities.push(e);
+            current_chunks.mapped.insert(chunk_pos);
         }
+
+        current_chunks
+            .entities
+            .entry(chunk_pos)
+            .or_default()
+            .extend(entities.iter().copied());
+        ev_loaded.send(ChunkLoaded {
+            chunk_pos,
+            entities,
+        });
+    }
+}
+
+fn update_terrain_lod(
+    camera_q: Query<&OrthographicProjection, With<Camera>>,
+    settings: Res<ChunkStreamingSettings>,
+    player_pos: Res<CurrentPlayerChunkPos>,
+    mut lod: ResMut<TerrainLod>,
+    mut ev_writer: EventWriter<PlayerChunkUpdateEvent>,
+    mut lod_q: Query<(&ChunkLod, &mut Visibility), Without<ChunkDetail>>,
+    mut detail_q: Query<(&ChunkDetail, &mut Visibility), Without<ChunkLod>>,
+) {
+    let Ok(projection) = camera_q.get_single() else {
+        return;
+    };
+
+    let zoomed_out = projection.scale > settings.lod_zoom;
+    if zoomed_out != lod.zoomed_out {
+        lod.zoomed_out = zoomed_out;
+        // Load or unload the chunks that are only visible zoomed out
+        ev_writer.send(PlayerChunkUpdateEvent(player_pos.0));
     }
 
-    (tiles, ground_map)
+    let show_lod =
+        |chunk_pos: ChunkPos| zoomed_out || chunk_pos.distance(player_pos.0) > settings.load_radius;
+    for (chunk, mut visibility) in lod_q.iter_mut() {
+        visibility.set_if_neq(if show_lod(chunk.0) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    for (chunk, mut visibility) in detail_q.iter_mut() {
+        visibility.set_if_neq(if show_lod(chunk.0) {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
 }
 
-fn process_tile((x, y): (i32, i32), occupied: &HashSet<(i32, i32)>) -> (i32, usize) {
-    let nei_options = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-    let mut nei = [1, 1, 1, 1];
-    let mut nei_count = 4;
-    for (idx, (i, j)) in nei_options.iter().enumerate() {
-        if !occupied.contains(&(x + i, y + j)) {
-            nei[idx] = 0;
-            nei_count -= 1;
+impl Default for ChunkStreamingSettings {
+    fn default() -> Self {
+        Self {
+            load_radius: CHUNK_LOAD_RADIUS,
+            unload_radius: CHUNK_UNLOAD_RADIUS,
+            preload_ahead: CHUNK_PRELOAD_AHEAD,
+            lod_radius: CHUNK_LOD_RADIUS,
+            lod_zoom: LOD_ZOOM_SCALE,
         }
     }
+}
 
-    let tile = match nei {
-        [0, 1, 1, 0] => 3,
-        [1, 0, 1, 0] => 4,
-        [0, 1, 0, 1] => 1,
-        [1, 0, 0, 1] => 2,
-        _ => 0,
-    };
+impl ChunkStreamingSettings {
+    /// Chunks that should be loaded around `center`, nearest first, including the ones ahead of
+    /// a player moving in `direction`
+    pub fn chunks_to_load(
+        &self,
+        center: ChunkPos,
+        direction: (i32, i32),
+        zoomed_out: bool,
+    ) -> Vec<ChunkPos> {
+        let radius = if zoomed_out {
+            self.lod_radius.max(self.load_radius)
+        } else {
+            self.load_radius
+        } as i32;
+        let mut chunks = HashSet::new();
+        for ahead in 0..=self.preload_ahead as i32 {
+            let (x, y) = (
+                center.x + direction.0 * ahead,
+                center.y + direction.1 * ahead,
+            );
+            for i in -radius..=radius {
+                for j in -radius..=radius {
+                    chunks.insert(ChunkPos::new(x + i, y + j));
+                }
+            }
+        }
 
-    (nei_count, tile)
-}
+        let mut chunks: Vec<ChunkPos> = chunks.into_iter().collect();
+        chunks.sort_by_key(|chunk_pos| (chunk_pos.distance(center), *chunk_pos));
+        chunks
+    }
 
-impl Tile {
-    fn new(pos: (i32, i32), sprite: usize, z_index: i32, seed: u32) -> Self {
-        let chunk_pos = grid_to_chunk(pos.0 as f32, pos.1 as f32);
-        let puid = gen_puid(pos, chunk_pos, seed);
-        Self {
-            puid,
-            pos,
-            sprite,
-            z_index,
+    /// Chunks further than this are unloaded, zoomed out they are kept out to `lod_radius`
+    pub fn unload_radius_for(&self, zoomed_out: bool) -> u32 {
+        if zoomed_out {
+            self.unload_radius.max(self.lod_radius + 1)
+        } else {
+            self.unload_radius
         }
     }
+}
 
+impl Tile {
     fn puid_component(&self) -> PUID {
         PUID(self.puid)
     }
 }
 
-// Gen a deterministic unique id
-fn gen_puid(pos: (i32, i32), chunk_pos: (i32, i32), seed: u32) -> u32 {
-    let (x, y) = pos;
-    let (chunk_x, chunk_y) = chunk_pos;
+impl AssetLoader for TerrainRulesLoader {
+    type Asset = TerrainRulesAsset;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let rules = ron::de::from_bytes::<TerrainRules>(&bytes)?;
+            rules.check()?;
+            Ok(TerrainRulesAsset(rules))
+        })
+    }
 
-    let base = ((x + chunk_x * 31) * 31 + y + chunk_y * 31) as u32;
-    base.wrapping_add(seed)
+    fn extensions(&self) -> &[&str] {
+        &["rules.ron"]
+    }
 }
@@ -0,0 +1,179 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::biome::BiomeCell;
+use crate::chunk_tiles::ChunkTiles;
+use crate::coords::{ChunkPos, GridPos};
+use crate::elevation::ElevationBand;
+use crate::terrain::{GenerationRules, GenerationSeed, TerrainTiles};
+use crate::water::WaterClass;
+use crate::worldgen::WorldGenerator;
+
+// Chunks regenerated for queries outside the loaded ones are forgotten past this many
+const MAX_REGENERATED_CHUNKS: usize = 16;
+
+/// What is at one grid cell of the terrain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerrainCell {
+    pub pos: GridPos,
+    pub ground: bool,
+    pub beach: bool,
+    pub cliff: bool,
+    /// Roads and bridges
+    pub road: bool,
+    pub band: ElevationBand,
+    /// Sea and lakes, rivers are neither ground nor classified water
+    pub water: Option<WaterClass>,
+}
+
+impl TerrainCell {
+    /// The cell at `pos` of a chunk's tiles, `pos` has to be inside the chunk
+    pub fn from_chunk(tiles: &ChunkTiles, pos: GridPos) -> Self {
+        let cell = pos.into();
+        Self {
+            pos,
+            ground: tiles.ground.contains(cell),
+            beach: tiles.beach.contains(cell),
+            cliff: tiles.cliffs.contains(cell),
+            road: tiles.roads.contains(cell),
+            band: tiles.band_at(cell),
+            water: tiles.water_at(cell),
+        }
+    }
+
+    /// Whether the player stands on land here, cliffs can only be stepped off
+    pub fn is_walkable(&self) -> bool {
+        self.ground || self.road
+    }
+
+    /// Cliffs are the top edge of a drop, so they can't be climbed from the band below, but the
+    /// player can walk onto them from the high ground and step off them. Climbing into a higher
+    /// band is only open one band at a time and straight ahead, a diagonal step could slip
+    /// between two cliffs that meet at a corner.
+    pub fn blocks_step_from(&self, from: &TerrainCell) -> bool {
+        if self.band <= from.band {
+            return false;
+        }
+
+        let diagonal = self.pos.x != from.pos.x && self.pos.y != from.pos.y;
+        self.cliff || diagonal || self.band as u8 - from.band as u8 > 1
+    }
+}
+
+/// Looks up the terrain at any grid cell.
+///
+/// Cells are read from the chunks in `TerrainTiles`. A chunk that isn't loaded is regenerated from
+/// the seed, which gives the same answer the chunk will once it is loaded but is slow, so those
+/// chunks are kept for the next queries of the same system.
+#[derive(SystemParam)]
+pub struct TerrainMap<'w, 's> {
+    tiles: Res<'w, TerrainTiles>,
+    seed: Res<'w, GenerationSeed>,
+    rules: Res<'w, GenerationRules>,
+    regenerated: Local<'s, RegeneratedChunks>,
+}
+
+/// Chunks `TerrainMap` regenerated itself, kept per system
+#[derive(Default)]
+pub struct RegeneratedChunks {
+    generator: Option<WorldGenerator>,
+    chunks: HashMap<ChunkPos, ChunkTiles>,
+}
+
+impl TerrainMap<'_, '_> {
+    /// The cell at `pos`, regenerating its chunk if it isn't loaded
+    pub fn tile_at(&mut self, pos: GridPos) -> TerrainCell {
+        TerrainCell::from_chunk(self.chunk(pos), pos)
+    }
+
+    /// Biomes come straight from the noise, so they don't need the chunk to be loaded
+    pub fn biome_at(&mut self, pos: GridPos) -> BiomeCell {
+        self.generator().biome_at(pos.into())
+    }
+
+    pub fn is_walkable(&mut self, pos: GridPos) -> bool {
+        self.tile_at(pos).is_walkable()
+    }
+
+    /// Sea, lakes and rivers, bridges included
+    pub fn is_water(&mut self, pos: GridPos) -> bool {
+        !self.tile_at(pos).ground
+    }
+
+    /// Closest cell to `from`, no further than `radius`, whose tile matches `predicate`. Ties go to
+    /// the smallest position so that the answer doesn't depend on the search order.
+    pub fn nearest_tile_matching(
+        &mut self,
+        from: GridPos,
+        radius: u32,
+        predicate: impl Fn(&TerrainCell) -> bool,
+    ) -> Option<GridPos> {
+        let radius = radius as i32;
+        let mut best: Option<(i32, GridPos)> = None;
+
+        for ring in 0..=radius {
+            // Cells of further rings are at least `ring` away
+            if best.is_some_and(|(distance, _)| distance < ring * ring) {
+                break;
+            }
+
+            for dx in -ring..=ring {
+                for dy in -ring..=ring {
+                    if dx.abs() != ring && dy.abs() != ring {
+                        continue;
+                    }
+
+                    let distance = dx * dx + dy * dy;
+                    let pos = GridPos::new(from.x + dx, from.y + dy);
+                    if distance > radius * radius || best.is_some_and(|b| b <= (distance, pos)) {
+                        continue;
+                    }
+                    if predicate(&self.tile_at(pos)) {
+                        best = Some((distance, pos));
+                    }
+                }
+            }
+        }
+
+        best.map(|(_, pos)| pos)
+    }
+
+    /// Tiles of the chunk of `pos`, regenerated if it isn't loaded
+    fn chunk(&mut self, pos: GridPos) -> &ChunkTiles {
+        let chunk_pos = pos.chunk();
+        if self.tiles.0.contains_key(&chunk_pos) {
+            return &self.tiles.0[&chunk_pos];
+        }
+
+        self.generator();
+        let regenerated = &mut *self.regenerated;
+        if !regenerated.chunks.contains_key(&chunk_pos) {
+            if regenerated.chunks.len() >= MAX_REGENERATED_CHUNKS {
+                regenerated.chunks.clear();
+            }
+            let generator = regenerated.generator.as_ref().unwrap();
+            let region = generator.generate_chunk(chunk_pos.into());
+            let tiles = ChunkTiles::from_region(chunk_pos.into(), &region);
+            regenerated.chunks.insert(chunk_pos, tiles);
+        }
+        &regenerated.chunks[&chunk_pos]
+    }
+
+    /// Generator for the current seed and rules, rebuilt when either changes
+    fn generator(&mut self) -> &WorldGenerator {
+        let regenerated = &mut *self.regenerated;
+        let is_current = regenerated.generator.as_ref().is_some_and(|generator| {
+            generator.seed() == self.seed.0.terrain()
+                && std::ptr::eq(generator.rules(), &*self.rules.0)
+        });
+        if !is_current {
+            regenerated.generator = Some(WorldGenerator::with_rules(
+                self.seed.0.terrain(),
+                self.rules.0.clone(),
+            ));
+            regenerated.chunks.clear();
+        }
+        regenerated.generator.as_ref().unwrap()
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(test)]
+use std::sync::Arc;
+
+#[cfg(test)]
+use bevy::ecs::system::SystemState;
+#[cfg(test)]
+use bevy::prelude::*;
+
+#[cfg(test)]
+use crate::{
+    chunk_tiles::ChunkTiles,
+    coords::{ChunkPos, GridPos},
+    elevation::ElevationBand,
+    rules::TerrainRules,
+    seed::Seed,
+    terrain::{GenerationRules, GenerationSeed, TerrainTiles},
+    terrain_map::{TerrainCell, TerrainMap},
+    worldgen::WorldGenerator,
+};
+
+// A world with the resources `TerrainMap` reads, with the chunks in `loaded` loaded
+#[cfg(test)]
+fn terrain_world(seed: Seed, loaded: &[(i32, i32)]) -> World {
+    let generator = WorldGenerator::new(seed.terrain());
+    let mut tiles = TerrainTiles::default();
+    for chunk_pos in loaded {
+        let region = generator.generate_chunk(*chunk_pos);
+        tiles.0.insert(
+            ChunkPos::from(*chunk_pos),
+            ChunkTiles::from_region(*chunk_pos, &region),
+        );
+    }
+
+    let mut world = World::new();
+    world.insert_resource(tiles);
+    world.insert_resource(GenerationSeed(seed));
+    world.insert_resource(GenerationRules(Arc::new(TerrainRules::default())));
+    world
+}
+
+#[test]
+fn test_terrain_map_matches_loaded_chunks() {
+    let seed = Seed(1234);
+    let loaded = (0, 0);
+    let mut world = terrain_world(seed, &[loaded]);
+    let mut state: SystemState<TerrainMap> = SystemState::new(&mut world);
+    let mut terrain = state.get_mut(&mut world);
+
+    let region = WorldGenerator::new(seed.terrain()).generate_chunk(loaded);
+    let rect = region.rect;
+    for x in (rect.x..rect.end().0).step_by(7) {
+        for y in (rect.y..rect.end().1).step_by(5) {
+            let pos = GridPos::new(x, y);
+            let cell = terrain.tile_at(pos);
+            assert_eq!(cell.ground, region.ground.contains(&(x, y)));
+            assert_eq!(cell.water, region.water_at((x, y)));
+            assert_eq!(terrain.is_water(pos), !cell.ground);
+            assert_eq!(
+                terrain.biome_at(pos).biome,
+                region.biome_at((x, y)).unwrap().biome
+            );
+        }
+    }
+}
+
+#[test]
+fn test_unloaded_chunks_are_generated_on_request() {
+    let seed = Seed(1234);
+    let unloaded = (-1, 0);
+    let mut world = terrain_world(seed, &[(0, 0)]);
+    let mut state: SystemState<TerrainMap> = SystemState::new(&mut world);
+    let mut terrain = state.get_mut(&mut world);
+
+    // Land of a chunk that isn't loaded reads as land, not as water
+    let region = WorldGenerator::new(seed.terrain()).generate_chunk(unloaded);
+    let rect = region.rect;
+    let mut generated = Vec::new();
+    for x in (rect.x..rect.end().0).step_by(7) {
+        for y in (rect.y..rect.end().1).step_by(5) {
+            let pos = GridPos::new(x, y);
+            let cell = terrain.tile_at(pos);
+            assert_eq!(cell.ground, region.ground.contains(&(x, y)));
+            assert_eq!(cell.water, region.water_at((x, y)));
+            assert_eq!(terrain.is_water(pos), !cell.ground);
+            assert_eq!(terrain.is_walkable(pos), cell.is_walkable());
+            generated.push(cell);
+        }
+    }
+    assert!(generated.iter().any(|cell| cell.ground));
+
+    // Once loaded the chunk reads the same as it was generated
+    let mut world = terrain_world(seed, &[(0, 0), unloaded]);
+    let mut state: SystemState<TerrainMap> = SystemState::new(&mut world);
+    let mut terrain = state.get_mut(&mut world);
+    for cell in generated {
+        assert_eq!(terrain.tile_at(cell.pos), cell);
+    }
+}
+
+#[test]
+fn test_nearest_tile_matching() {
+    let mut world = terrain_world(Seed(1234), &[(-1, -1), (0, -1), (-1, 0), (0, 0)]);
+    let mut state: SystemState<TerrainMap> = SystemState::new(&mut world);
+    let mut terrain = state.get_mut(&mut world);
+
+    let from = GridPos::new(10, 10);
+    let nearest = terrain.nearest_tile_matching(from, 40, |cell| cell.ground);
+    assert!(nearest.is_some());
+
+    // Same as checking every cell in the radius
+    let mut expected: Option<(i32, GridPos)> = None;
+    for x in from.x - 40..=from.x + 40 {
+        for y in from.y - 40..=from.y + 40 {
+            let pos = GridPos::new(x, y);
+            let distance = (x - from.x).pow(2) + (y - from.y).pow(2);
+            if distance > 40 * 40 || !terrain.tile_at(pos).ground {
+                continue;
+            }
+            if expected.is_none_or(|e| (distance, pos) < e) {
+                expected = Some((distance, pos));
+            }
+        }
+    }
+    assert_eq!(nearest, expected.map(|(_, pos)| pos));
+    assert_eq!(terrain.nearest_tile_matching(from, 40, |_| false), None);
+}
+
+#[test]
+fn test_cliffs_block_climbing_only() {
+    let cell = |x, band, cliff| TerrainCell {
+        pos: GridPos::new(x, 0),
+        ground: true,
+        beach: false,
+        cliff,
+        road: false,
+        band,
+        water: None,
+    };
+    let lowland = cell(0, ElevationBand::Lowland, false);
+    let edge = cell(1, ElevationBand::Hills, true);
+    let plateau = cell(2, ElevationBand::Hills, false);
+    let peak_edge = cell(3, ElevationBand::Mountains, true);
+
+    // Up onto a cliff from the band below is blocked
+    assert!(edge.blocks_step_from(&lowland));
+    assert!(peak_edge.blocks_step_from(&plateau));
+    assert!(peak_edge.blocks_step_from(&edge));
+
+    // Onto the edge from the high ground and off it down the drop are not
+    assert!(!edge.blocks_step_from(&plateau));
+    assert!(!lowland.blocks_step_from(&edge));
+    assert!(!plateau.blocks_step_from(&edge));
+    assert!(!edge.blocks_step_from(&edge));
+    assert!(!plateau.blocks_step_from(&lowland));
+
+    // Straight from the lowland up to the mountains is a drop even without a cliff
+    let peak = cell(3, ElevationBand::Mountains, false);
+    assert!(peak.blocks_step_from(&lowland));
+    assert!(!lowland.blocks_step_from(&peak));
+}
+
+#[test]
+fn test_cliffs_block_climbing_diagonally() {
+    let cell = |x, y, band, cliff| TerrainCell {
+        pos: GridPos::new(x, y),
+        ground: true,
+        beach: false,
+        cliff,
+        road: false,
+        band,
+        water: None,
+    };
+    // Two cliffs meet at a corner, the plateau behind them isn't a cliff itself
+    let lowland = cell(0, 0, ElevationBand::Lowland, false);
+    let east_edge = cell(1, 0, ElevationBand::Hills, true);
+    let north_edge = cell(0, 1, ElevationBand::Hills, true);
+    let plateau = cell(1, 1, ElevationBand::Hills, false);
+    assert!(east_edge.blocks_step_from(&lowland));
+    assert!(north_edge.blocks_step_from(&lowland));
+
+    // Cutting the corner up onto the plateau is blocked, stepping down across it is not
+    assert!(plateau.blocks_step_from(&lowland));
+    assert!(!lowland.blocks_step_from(&plateau));
+
+    // Diagonal steps within a band are open
+    let field = cell(1, 1, ElevationBand::Lowland, false);
+    assert!(!field.blocks_step_from(&lowland));
+    assert!(!north_edge.blocks_step_from(&east_edge));
+}
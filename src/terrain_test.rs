@@ -0,0 +1,37 @@
+#[cfg(test)]
+use crate::{coords::ChunkPos, terrain::ChunkStreamingSettings};
+
+#[test]
+fn test_chunks_to_load() {
+    let settings = ChunkStreamingSettings {
+        load_radius: 1,
+        unload_radius: 2,
+        preload_ahead: 1,
+        lod_radius: 3,
+        lod_zoom: 3.0,
+    };
+
+    let center = ChunkPos::new(4, -2);
+    let idle = settings.chunks_to_load(center, (0, 0), false);
+    assert_eq!(idle.len(), 9);
+    assert_eq!(idle[0], center);
+
+    // Walking east loads the next column of chunks too
+    let east = settings.chunks_to_load(center, (1, 0), false);
+    assert_eq!(east.len(), 12);
+    for j in -3..=-1 {
+        assert!(east.contains(&ChunkPos::new(6, j)));
+    }
+
+    // Nothing that gets loaded is unloaded right away
+    for chunk_pos in east {
+        assert!(chunk_pos.distance(center) <= settings.unload_radius_for(false));
+    }
+
+    // Zoomed out, chunks further away are loaded to be drawn as a map
+    let zoomed_out = settings.chunks_to_load(center, (0, 0), true);
+    assert_eq!(zoomed_out.len(), 49);
+    for chunk_pos in zoomed_out {
+        assert!(chunk_pos.distance(center) <= settings.unload_radius_for(true));
+    }
+}
@@ -1,9 +1,9 @@
 #[cfg(test)]
-use crate::grid::*;
+use crate::{grid::*, seed::Seed};
 
 #[test]
 fn test_maze() {
-    let maze = Grid::new_maze(8, 12, 1234);
+    let maze = Grid::new_maze(8, 12, Seed(1234));
 
     assert_eq!(maze.width(), 8);
     assert_eq!(maze.height(), 12);
@@ -13,13 +13,13 @@ fn test_maze() {
 
 #[test]
 fn test_is_wall_between() {
-    let maze = Grid::new_maze(2, 2, 1234);
+    let maze = Grid::new_maze(2, 2, Seed(1234));
 
     // Vertical wall between two cells
-    assert_eq!(maze.is_wall_between((1, 0), (0, 0)), true);
-    assert_eq!(maze.is_wall_between((0, 0), (1, 0)), true);
+    assert!(!maze.is_wall_between((1, 0), (0, 0)));
+    assert!(!maze.is_wall_between((0, 0), (1, 0)));
 
     // Horizontal wall between two cells
-    assert_eq!(maze.is_wall_between((0, 1), (0, 0)), false);
-    assert_eq!(maze.is_wall_between((0, 0), (0, 1)), false);
+    assert!(maze.is_wall_between((0, 1), (0, 0)));
+    assert!(maze.is_wall_between((0, 0), (0, 1)));
 }
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+
+use crate::coords::{GridPos, WorldPos};
+use crate::worldgen::Tile;
+use crate::*;
+
+/// Tiles that gameplay looks up, they keep their own entity instead of being batched
+pub fn is_interactive(tile: &Tile) -> bool {
+    INTERACTIVE_SPRITE_INDICES.contains(&tile.sprite)
+}
+
+/// One mesh per z index, with a textured quad for every tile of that layer.
+///
+/// Quads are in world space, so the meshes are drawn with an identity transform apart from z.
+/// `image_size` is the size in pixels of the sprite sheet `atlas` is cut from.
+pub fn build_layer_meshes<'a>(
+    tiles: impl IntoIterator<Item = &'a Tile>,
+    atlas: &TextureAtlas,
+    image_size: Vec2,
+) -> Vec<(i32, Mesh)> {
+    let mut layers: BTreeMap<i32, Vec<&Tile>> = BTreeMap::new();
+    for t in tiles {
+        layers.entry(t.z_index).or_default().push(t);
+    }
+
+    let half_w = TILE_W as f32 * SPRITE_SCALE_FACTOR as f32 / 2.0;
+    let half_h = TILE_H as f32 * SPRITE_SCALE_FACTOR as f32 / 2.0;
+
+    layers
+        .into_iter()
+        .map(|(z_index, tiles)| {
+            let mut positions = Vec::with_capacity(tiles.len() * 4);
+            let mut uvs = Vec::with_capacity(tiles.len() * 4);
+            let mut indices = Vec::with_capacity(tiles.len() * 6);
+
+            for t in tiles {
+                let WorldPos { x, y } = GridPos::from(t.pos).world();
+                let rect = atlas.textures[t.sprite];
+                let (uv_min, uv_max) = (rect.min / image_size, rect.max / image_size);
+
+                let i = positions.len() as u32;
+                positions.extend([
+                    [x - half_w, y - half_h, 0.0],
+                    [x + half_w, y - half_h, 0.0],
+                    [x + half_w, y + half_h, 0.0],
+                    [x - half_w, y + half_h, 0.0],
+                ]);
+                // Texture space is y down
+                uvs.extend([
+                    [uv_min.x, uv_max.y],
+                    [uv_max.x, uv_max.y],
+                    [uv_max.x, uv_min.y],
+                    [uv_min.x, uv_min.y],
+                ]);
+                indices.extend([i, i + 1, i + 2, i, i + 2, i + 3]);
+            }
+
+            let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+            mesh.set_indices(Some(Indices::U32(indices)));
+            (z_index, mesh)
+        })
+        .collect()
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+use bevy::{math::vec2, prelude::*, render::mesh::VertexAttributeValues};
+
+#[cfg(test)]
+use crate::{tilemap::*, worldgen::*, *};
+
+#[test]
+fn test_one_mesh_per_layer() {
+    let atlas = TextureAtlas::from_grid(
+        Handle::default(),
+        vec2(TILE_W as f32, TILE_H as f32),
+        SPRITE_SHEET_W,
+        SPRITE_SHEET_H,
+        Some(Vec2::splat(SPRITE_PADDING)),
+        Some(Vec2::splat(SPRITE_SHEET_OFFSET)),
+    );
+    let region = WorldGenerator::new(1234).generate_chunk((0, 0));
+    let (interactive, batched): (Vec<&Tile>, Vec<&Tile>) =
+        region.tiles.iter().partition(|t| is_interactive(t));
+    assert!(interactive.iter().all(|t| t.z_index > 0));
+
+    // The smallest sheet the grid fits in, its uvs are the largest the sprites can get
+    let image_size = atlas.size + Vec2::splat(SPRITE_SHEET_OFFSET);
+    let meshes = build_layer_meshes(batched.iter().copied(), &atlas, image_size);
+    let layers: Vec<i32> = meshes.iter().map(|(z, _)| *z).collect();
+    assert!(layers.windows(2).all(|w| w[0] < w[1]));
+
+    let mut quads = 0;
+    for (z_index, mesh) in meshes.iter() {
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("layer {z_index} has no uvs");
+        };
+        assert!(uvs.iter().flatten().all(|uv| (0.0..=1.0).contains(uv)));
+        assert_eq!(mesh.indices().unwrap().len(), uvs.len() / 4 * 6);
+        quads += uvs.len() / 4;
+    }
+    assert_eq!(quads, batched.len());
+}
@@ -1,61 +1,29 @@
 use bevy::ui::Val;
 use rand::prelude::*;
 
-use crate::*;
-
-pub fn grid_to_world(x: f32, y: f32) -> (f32, f32) {
-    (
-        x * TILE_W as f32 * SPRITE_SCALE_FACTOR as f32,
-        y * TILE_H as f32 * SPRITE_SCALE_FACTOR as f32,
-    )
+pub fn seed_to_rng(value: u32) -> StdRng {
+    let mut array: [u8; 32] = [0; 32];
+    array[..4].copy_from_slice(&value.to_le_bytes());
+    StdRng::from_seed(array)
 }
 
-pub fn world_to_grid(x: f32, y: f32) -> (f32, f32) {
-    (
-        (x / (TILE_W as f32 * SPRITE_SCALE_FACTOR as f32)).floor(),
-        (y / (TILE_H as f32 * SPRITE_SCALE_FACTOR as f32)).floor(),
+// Stateless hash of a lattice point, for placing things without walking an rng
+pub fn hash_2d(seed: u32, x: i64, y: i64) -> u64 {
+    mix_64(
+        seed as u64
+            ^ (x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            ^ (y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f),
     )
 }
 
-pub fn center_to_top_left_grid(x: f32, y: f32) -> (f32, f32) {
-    let x_center = x + GRID_COLS as f32 / 2.0;
-    let y_center = GRID_ROWS as f32 / 2.0 - y;
-    (x_center, y_center)
-}
-
-pub fn center_to_top_left(x: f32, y: f32) -> (f32, f32) {
-    let x_center = x - (GRID_W as f32 * SPRITE_SCALE_FACTOR as f32) / 2.0;
-    let y_center = (GRID_H as f32 * SPRITE_SCALE_FACTOR as f32) / 2.0 - y;
-    (x_center, y_center)
-}
-
-pub fn grid_to_chunk(x: f32, y: f32) -> (i32, i32) {
-    let (x, y) = (x / CHUNK_W as f32, y / CHUNK_H as f32);
-    (x.floor() as i32, y.floor() as i32)
-}
-
-pub fn world_to_chunk(x: f32, y: f32) -> (i32, i32) {
-    let (x, y) = world_to_grid(x, y);
-    grid_to_chunk(x, y)
-}
-
-pub fn seed_from_seed_str(seed_str: String) -> u32 {
-    seed_str
-        .trim()
-        .split("")
-        .map(|c| {
-            c.as_bytes()
-                .iter()
-                .map(|i| i.to_owned() as u32)
-                .fold(0, |acc, i| acc + i)
-        })
-        .fold(0, |acc: u32, j: u32| acc.wrapping_add(j))
-}
-
-pub fn seed_to_rng(value: u32) -> StdRng {
-    let mut array: [u8; 32] = [0; 32];
-    array[..4].copy_from_slice(&value.to_le_bytes());
-    StdRng::from_seed(array)
+// Scrambles the bits of a u64, every input gives a different output
+pub fn mix_64(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^= h >> 33;
+    h
 }
 
 pub fn diff_exceeds_max(n1: f32, n2: f32, max: f32) -> bool {
@@ -102,6 +70,6 @@ pub fn px_val_between(val: Val, min: Val, max: Val) -> bool {
     px_val_greater_than_or_eq(val, min) && px_val_less_than_or_eq(val, max)
 }
 
-pub fn proc_gen_num(seed: u32, incr: usize, rem: usize) -> usize {
-    (((seed as usize * incr) as f64).sqrt()) as usize % rem
+pub fn proc_gen_num(seed: u64, incr: usize, rem: usize) -> usize {
+    (((seed as usize).wrapping_mul(incr) as f64).sqrt()) as usize % rem
 }
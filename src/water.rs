@@ -0,0 +1,95 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rules::WaterRules;
+use crate::worldgen::GridRect;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WaterClass {
+    /// Open sea close to the surface, along the coast
+    Shallow,
+    DeepOcean,
+    /// A body of water enclosed by land
+    Lake,
+}
+
+impl WaterClass {
+    pub fn sprite(&self, rules: &WaterRules) -> usize {
+        match self {
+            WaterClass::Shallow => rules.shallow_sprite,
+            WaterClass::DeepOcean => rules.deep_sprite,
+            WaterClass::Lake => rules.lake_sprite,
+        }
+    }
+
+    pub fn is_ocean(&self) -> bool {
+        matches!(self, WaterClass::Shallow | WaterClass::DeepOcean)
+    }
+}
+
+/// Classifies every cell of `rect` at or below `sea_level`.
+///
+/// A body of water is a lake when it is no bigger than `max_lake_size` cells. Bodies are flood
+/// filled past the edges of `rect`, so one that crosses them is measured whole.
+pub fn classify_water(
+    rules: &WaterRules,
+    sea_level: f64,
+    rect: GridRect,
+    height_at: impl Fn((i32, i32)) -> f64,
+) -> HashMap<(i32, i32), WaterClass> {
+    let mut heights = HashMap::new();
+    let mut height = |pos: (i32, i32)| *heights.entry(pos).or_insert_with(|| height_at(pos));
+
+    let mut water = HashMap::new();
+    // Cells already known to belong to the open sea
+    let mut ocean = HashSet::new();
+    let (end_x, end_y) = rect.end();
+
+    for x in rect.x..end_x {
+        for y in rect.y..end_y {
+            let elevation = height((x, y));
+            if elevation > sea_level || water.contains_key(&(x, y)) {
+                continue;
+            }
+
+            let mut body = HashSet::from([(x, y)]);
+            let mut queue = VecDeque::from([(x, y)]);
+            let mut is_lake = true;
+            while let Some((bx, by)) = queue.pop_front() {
+                if ocean.contains(&(bx, by)) || body.len() > rules.max_lake_size {
+                    is_lake = false;
+                    break;
+                }
+
+                for (i, j) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    let nei = (bx + i, by + j);
+                    if !body.contains(&nei) && height(nei) <= sea_level {
+                        body.insert(nei);
+                        queue.push_back(nei);
+                    }
+                }
+            }
+
+            for pos in body.iter() {
+                if !rect.contains(*pos) {
+                    continue;
+                }
+
+                let class = if is_lake {
+                    WaterClass::Lake
+                } else if height(*pos) > rules.shallow_level {
+                    WaterClass::Shallow
+                } else {
+                    WaterClass::DeepOcean
+                };
+                water.insert(*pos, class);
+            }
+            if !is_lake {
+                ocean.extend(body);
+            }
+        }
+    }
+
+    water
+}
@@ -0,0 +1,632 @@
+use std::cell::{OnceCell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use noise::NoiseFn;
+
+use crate::autotile::{blob_index, neighbour_mask};
+use crate::biome::{Biome, BiomeCell};
+use crate::elevation::ElevationBand;
+use crate::fractal::FractalNoise;
+use crate::river::river_cells;
+use crate::road::{find_road, Crossing, Road};
+use crate::rules::{Decoration, TerrainRules};
+use crate::scatter::scatter;
+use crate::settlement::{settlement_at, site_bounds, sites_in, Settlement};
+use crate::utils::mix_64;
+use crate::water::{classify_water, WaterClass};
+use crate::*;
+
+// Villages and roads laid out by a generator are forgotten past this many of each
+const MAX_CACHED_LAYOUTS: usize = 4096;
+
+/// Lattice cells of the two villages a road joins
+type SitePair = ((i32, i32), (i32, i32));
+
+/// A rectangle of `w` by `h` grid cells whose top left cell is `(x, y)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GridRect {
+    pub x: i32,
+    pub y: i32,
+    pub w: usize,
+    pub h: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Tile {
+    pub puid: u64,
+    pub pos: (i32, i32),
+    pub sprite: usize,
+    pub z_index: i32,
+}
+
+/// Everything generated for a `GridRect`, with no dependency on the game world
+#[derive(Clone, Debug)]
+pub struct Region {
+    pub rect: GridRect,
+    pub tiles: Vec<Tile>,
+    pub ground: HashSet<(i32, i32)>,
+    /// Ground cells that are sand
+    pub beach: HashSet<(i32, i32)>,
+    /// Ground cells at the top of a drop into a lower elevation band
+    pub cliffs: HashSet<(i32, i32)>,
+    pub rivers: HashSet<(i32, i32)>,
+    pub water: HashMap<(i32, i32), WaterClass>,
+    pub biomes: Vec<BiomeCell>,
+    /// Villages with at least one cell in the region
+    pub settlements: Vec<Settlement>,
+    /// Road cells between the villages, bridges included
+    pub roads: HashSet<(i32, i32)>,
+    pub bridges: HashSet<(i32, i32)>,
+}
+
+/// Generates terrain for any part of the world from a seed. Everything is derived from the seed
+/// and the position alone, so a cell comes out the same whichever chunk or rect it is generated
+/// in and chunks meet without seams. Clones share the villages and roads already laid out, so
+/// neighbouring chunks don't search for the same roads again.
+#[derive(Clone)]
+pub struct WorldGenerator {
+    seed: u32,
+    rules: Arc<TerrainRules>,
+    elevation_noise: FractalNoise,
+    temperature_noise: FractalNoise,
+    moisture_noise: FractalNoise,
+    layouts: Arc<Mutex<LayoutCache>>,
+}
+
+/// Villages by lattice cell, and roads by the lattice cells of the villages they join
+#[derive(Default)]
+struct LayoutCache {
+    settlements: HashMap<(i32, i32), Option<Settlement>>,
+    roads: HashMap<SitePair, Option<Road>>,
+}
+
+impl GridRect {
+    pub fn new(x: i32, y: i32, w: usize, h: usize) -> Self {
+        Self { x, y, w, h }
+    }
+
+    pub fn from_chunk((x, y): (i32, i32)) -> Self {
+        Self::new(x * CHUNK_W as i32, y * CHUNK_H as i32, CHUNK_W, CHUNK_H)
+    }
+
+    pub fn end(&self) -> (i32, i32) {
+        (self.x + self.w as i32, self.y + self.h as i32)
+    }
+
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        let (end_x, end_y) = self.end();
+        x >= self.x && x < end_x && y >= self.y && y < end_y
+    }
+
+    pub fn intersects(&self, other: &GridRect) -> bool {
+        let (end_x, end_y) = self.end();
+        let (other_end_x, other_end_y) = other.end();
+        self.x < other_end_x && other.x < end_x && self.y < other_end_y && other.y < end_y
+    }
+
+    /// `self` grown by `margin` cells on every side
+    pub fn expand(&self, margin: i32) -> Self {
+        Self::new(
+            self.x - margin,
+            self.y - margin,
+            (self.w as i32 + 2 * margin) as usize,
+            (self.h as i32 + 2 * margin) as usize,
+        )
+    }
+}
+
+impl Region {
+    pub fn biome_at(&self, (x, y): (i32, i32)) -> Option<&BiomeCell> {
+        if !self.rect.contains((x, y)) {
+            return None;
+        }
+
+        let idx = (y - self.rect.y) as usize * self.rect.w + (x - self.rect.x) as usize;
+        self.biomes.get(idx)
+    }
+
+    pub fn water_at(&self, pos: (i32, i32)) -> Option<WaterClass> {
+        self.water.get(&pos).copied()
+    }
+}
+
+impl WorldGenerator {
+    pub fn new(seed: u32) -> Self {
+        Self::with_rules(seed, Arc::new(TerrainRules::default()))
+    }
+
+    pub fn with_rules(seed: u32, rules: Arc<TerrainRules>) -> Self {
+        Self {
+            seed,
+            elevation_noise: rules.elevation.build(seed),
+            temperature_noise: rules.temperature.build(seed.wrapping_add(1)),
+            moisture_noise: rules.moisture.build(seed.wrapping_add(2)),
+            rules,
+            layouts: Arc::default(),
+        }
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    pub fn rules(&self) -> &TerrainRules {
+        &self.rules
+    }
+
+    pub fn generate_chunk(&self, chunk_pos: (i32, i32)) -> Region {
+        self.generate(GridRect::from_chunk(chunk_pos))
+    }
+
+    pub fn generate(&self, rect: GridRect) -> Region {
+        let mut tiles = Vec::new();
+        let mut biomes = Vec::with_capacity(rect.w * rect.h);
+        let (end_x, end_y) = rect.end();
+
+        let river_map = self.rivers_in(rect.expand(1));
+        let mut heights = HashMap::new();
+        let mut ground_map = HashSet::new();
+        for x in rect.x - 1..end_x + 1 {
+            for y in rect.y - 1..end_y + 1 {
+                let height = self.height_at((x, y));
+                heights.insert((x, y), height);
+                // Rivers cut through the ground, so their banks get shore tiles like the coast
+                if river_map.contains(&(x, y)) {
+                    continue;
+                }
+                if height > self.rules.thresholds.sea_level {
+                    ground_map.insert((x, y));
+                }
+            }
+        }
+
+        let bands = &self.rules.bands;
+        let cliffs: HashSet<(i32, i32)> = ground_map
+            .iter()
+            .copied()
+            .filter(|pos| rect.contains(*pos))
+            .filter(|&(x, y)| {
+                let height = heights[&(x, y)];
+                [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .iter()
+                    .any(|(dx, dy)| bands.is_cliff(height, heights[&(x + dx, y + dy)]))
+            })
+            .collect();
+        for pos in cliffs.iter() {
+            tiles.push(Tile::new(
+                *pos,
+                bands.cliff_sprite,
+                bands.cliff_z_index,
+                self.seed,
+            ));
+        }
+
+        for y in rect.y..end_y {
+            for x in rect.x..end_x {
+                biomes.push(self.biome_with((x, y), heights[&(x, y)]));
+            }
+        }
+
+        // Sample one cell past the edges so that border road tiles know their neighbours
+        let nearby = self.settlements_in(rect.expand(1));
+        let road_list = self.roads_in(rect.expand(1));
+        let village_paths: HashSet<(i32, i32)> = nearby
+            .iter()
+            .flat_map(|s| std::iter::once(s.centre).chain(s.paths.iter().copied()))
+            .collect();
+        let village_cells: HashSet<(i32, i32)> = nearby.iter().flat_map(|s| s.cells()).collect();
+        let road_map: HashSet<(i32, i32)> = road_list
+            .iter()
+            .flat_map(|road| road.cells.iter().copied())
+            .filter(|pos| !village_cells.contains(pos))
+            .collect();
+        let settlements: Vec<Settlement> = nearby
+            .into_iter()
+            .filter(|s| s.cells().any(|pos| rect.contains(pos)))
+            .collect();
+
+        let mut built = HashSet::new();
+        let settlement_rules = &self.rules.settlements;
+        for settlement in settlements.iter() {
+            let structures = std::iter::once((settlement.centre, settlement_rules.well_sprite))
+                .chain(settlement.houses.iter().copied())
+                .chain(
+                    settlement
+                        .fences
+                        .iter()
+                        .map(|pos| (*pos, settlement_rules.fence_sprite)),
+                )
+                .map(|(pos, sprite)| (pos, sprite, settlement_rules.z_index));
+            let paths = settlement.paths.iter().map(|pos| {
+                (
+                    *pos,
+                    settlement_rules.path_sprite,
+                    settlement_rules.path_z_index,
+                )
+            });
+            for (pos, sprite, z_index) in structures.chain(paths) {
+                if rect.contains(pos) {
+                    built.insert(pos);
+                    tiles.push(Tile::new(pos, sprite, z_index, self.seed));
+                }
+            }
+        }
+
+        let road_rules = &self.rules.roads;
+        let mut roads = HashSet::new();
+        let mut bridges = HashSet::new();
+        for &(x, y) in road_map.iter() {
+            if !rect.contains((x, y)) {
+                continue;
+            }
+
+            let is_water =
+                river_map.contains(&(x, y)) || heights[&(x, y)] <= self.rules.thresholds.sea_level;
+            let sprite = if is_water {
+                bridges.insert((x, y));
+                road_rules.bridge_sprite
+            } else {
+                let connects = |pos| road_map.contains(&pos) || village_paths.contains(&pos);
+                let mask = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (dx, dy))| connects((x + dx, y + dy)))
+                    .fold(0, |mask, (i, _)| mask | 1 << i);
+                road_rules.tileset + mask
+            };
+            roads.insert((x, y));
+            built.insert((x, y));
+            tiles.push(Tile::new((x, y), sprite, road_rules.z_index, self.seed));
+        }
+
+        // Props go on the cells nothing else was placed on
+        for (pos, decoration, sprite) in self.props_in(rect) {
+            if river_map.contains(&pos) || cliffs.contains(&pos) || built.contains(&pos) {
+                continue;
+            }
+            tiles.push(Tile::new(pos, sprite, decoration.z_index, self.seed));
+        }
+
+        let rivers: HashSet<(i32, i32)> = river_map
+            .into_iter()
+            .filter(|pos| rect.contains(*pos))
+            .collect();
+        let river_rules = &self.rules.rivers;
+        for pos in rivers.iter() {
+            tiles.push(Tile::new(
+                *pos,
+                river_rules.sprite,
+                river_rules.z_index,
+                self.seed,
+            ));
+        }
+
+        let water = self.water_in(rect);
+        let water_rules = &self.rules.water;
+        for (pos, class) in water.iter() {
+            tiles.push(Tile::new(
+                *pos,
+                class.sprite(water_rules),
+                water_rules.z_index,
+                self.seed,
+            ));
+        }
+
+        let mut ground = HashSet::new();
+        let mut beach = HashSet::new();
+        for x in rect.x..end_x {
+            for y in rect.y..end_y {
+                if !ground_map.contains(&(x, y)) {
+                    continue;
+                }
+
+                let idx = (y - rect.y) as usize * rect.w + (x - rect.x) as usize;
+                let tileset = if biomes[idx].biome == Biome::Beach {
+                    beach.insert((x, y));
+                    self.rules.sand_tileset
+                } else {
+                    self.rules.ground_tileset
+                };
+
+                let mask = neighbour_mask((x, y), |pos| ground_map.contains(&pos));
+                ground.insert((x, y));
+                tiles.push(Tile::new(
+                    (x, y),
+                    tileset + blob_index(mask),
+                    self.rules.ground_z_index,
+                    self.seed,
+                ));
+            }
+        }
+
+        Region {
+            rect,
+            tiles,
+            ground,
+            beach,
+            cliffs,
+            rivers,
+            water,
+            biomes,
+            settlements,
+            roads,
+            bridges,
+        }
+    }
+
+    pub fn height_at(&self, (x, y): (i32, i32)) -> f64 {
+        self.elevation_noise.get([x as f64, y as f64]) - self.rules.world.falloff((x, y))
+    }
+
+    /// River cells inside `rect`
+    pub fn rivers_in(&self, rect: GridRect) -> HashSet<(i32, i32)> {
+        river_cells(
+            self.seed,
+            &self.rules.rivers,
+            self.rules.thresholds.sea_level,
+            rect,
+            |pos| self.height_at(pos),
+        )
+    }
+
+    /// Water class of every sea level cell inside `rect`
+    pub fn water_in(&self, rect: GridRect) -> HashMap<(i32, i32), WaterClass> {
+        classify_water(
+            &self.rules.water,
+            self.rules.thresholds.sea_level,
+            rect,
+            |pos| self.height_at(pos),
+        )
+    }
+
+    /// Roads with at least one cell inside `rect`
+    pub fn roads_in(&self, rect: GridRect) -> Vec<Road> {
+        let rules = &self.rules;
+        let sites = rules
+            .roads
+            .max_length
+            .div_euclid(rules.settlements.site_spacing.max(1))
+            + 1;
+
+        // A road never leaves the box between its wells grown by `margin`, and that box is no
+        // wider than `max_length`, so only villages this close can send a road into `rect`
+        let mut roads = Vec::new();
+        let reach = rules.roads.max_length + rules.roads.margin;
+        for site in sites_in(&rules.settlements, rect.expand(reach)) {
+            let Some(from) = self.settlement_at(site) else {
+                continue;
+            };
+
+            for dx in -sites..=sites {
+                for dy in -sites..=sites {
+                    // Each pair is joined once, from the site that comes first
+                    if (dx, dy) <= (0, 0) {
+                        continue;
+                    }
+                    let Some(to) = self.settlement_at((site.0 + dx, site.1 + dy)) else {
+                        continue;
+                    };
+                    let Some(bounds) = self.road_bounds(&from, &to) else {
+                        continue;
+                    };
+                    if !bounds.intersects(&rect) {
+                        continue;
+                    }
+
+                    if let Some(road) = self.road_between(&from, &to, bounds) {
+                        if road.cells.iter().any(|pos| rect.contains(*pos)) {
+                            roads.push(road);
+                        }
+                    }
+                }
+            }
+        }
+
+        roads
+    }
+
+    /// Box a road between `from` and `to` is searched in, `None` if they are too far apart
+    fn road_bounds(&self, from: &Settlement, to: &Settlement) -> Option<GridRect> {
+        let rules = &self.rules.roads;
+        let ((ax, ay), (bx, by)) = (from.centre, to.centre);
+        let length = (((ax - bx).pow(2) + (ay - by).pow(2)) as f64).sqrt();
+        if length > rules.max_length as f64 {
+            return None;
+        }
+
+        Some(GridRect::new(
+            ax.min(bx) - rules.margin,
+            ay.min(by) - rules.margin,
+            ((ax - bx).abs() + 1 + 2 * rules.margin) as usize,
+            ((ay - by).abs() + 1 + 2 * rules.margin) as usize,
+        ))
+    }
+
+    /// Cheapest road from the well of `from` to the well of `to` inside `bounds`, laid out once
+    /// per generator
+    fn road_between(&self, from: &Settlement, to: &Settlement, bounds: GridRect) -> Option<Road> {
+        let key = (from.site, to.site);
+        if let Some(road) = self.layouts.lock().unwrap().roads.get(&key) {
+            return road.clone();
+        }
+
+        let rules = &self.rules;
+        let nearby = self.settlements_in(bounds);
+        let occupied: HashSet<(i32, i32)> = nearby
+            .iter()
+            .flat_map(|s| {
+                s.houses
+                    .iter()
+                    .map(|(pos, _)| *pos)
+                    .chain(s.fences.iter().copied())
+            })
+            .collect();
+        let paths: HashSet<(i32, i32)> = nearby
+            .iter()
+            .flat_map(|s| std::iter::once(s.centre).chain(s.paths.iter().copied()))
+            .collect();
+        let river_map = self.rivers_in(bounds);
+
+        // Every cell is looked at by its own step and by the cliff checks of its neighbours
+        let heights = RefCell::new(HashMap::new());
+        let height_at = |pos: (i32, i32)| -> f64 {
+            *heights
+                .borrow_mut()
+                .entry(pos)
+                .or_insert_with(|| self.height_at(pos))
+        };
+        let crossing_at = |pos: (i32, i32)| -> Crossing {
+            if occupied.contains(&pos) {
+                return Crossing::Blocked;
+            }
+            let height = height_at(pos);
+            if paths.contains(&pos) {
+                return Crossing::Land { height, cost: 0.0 };
+            }
+            if river_map.contains(&pos) || height <= rules.thresholds.sea_level {
+                return Crossing::Water;
+            }
+            if self.is_cliff(pos, height, height_at) {
+                return Crossing::Blocked;
+            }
+
+            let cost = if self.biome_with(pos, height).biome == Biome::Forest {
+                rules.roads.forest_cost
+            } else {
+                0.0
+            };
+            Crossing::Land { height, cost }
+        };
+
+        let road =
+            find_road(from.centre, to.centre, bounds, &rules.roads, crossing_at).map(|cells| {
+                Road {
+                    from: from.id,
+                    to: to.id,
+                    cells,
+                }
+            });
+
+        let mut layouts = self.layouts.lock().unwrap();
+        if layouts.roads.len() >= MAX_CACHED_LAYOUTS {
+            layouts.roads.clear();
+        }
+        layouts.roads.insert(key, road.clone());
+        road
+    }
+
+    /// Whether `pos`, at `height`, is at the top of a drop into a lower elevation band
+    fn is_cliff(
+        &self,
+        (x, y): (i32, i32),
+        height: f64,
+        height_at: impl Fn((i32, i32)) -> f64,
+    ) -> bool {
+        let bands = &self.rules.bands;
+        ElevationBand::classify(height, bands) != ElevationBand::Lowland
+            && [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .any(|(dx, dy)| bands.is_cliff(height, height_at((x + dx, y + dy))))
+    }
+
+    /// Villages with at least one cell inside `rect`
+    pub fn settlements_in(&self, rect: GridRect) -> Vec<Settlement> {
+        sites_in(&self.rules.settlements, rect)
+            .into_iter()
+            .filter_map(|site| self.settlement_at(site))
+            .filter(|s| s.cells().any(|pos| rect.contains(pos)))
+            .collect()
+    }
+
+    /// Village of the lattice cell `site`, laid out once per generator
+    fn settlement_at(&self, site: (i32, i32)) -> Option<Settlement> {
+        if let Some(settlement) = self.layouts.lock().unwrap().settlements.get(&site) {
+            return settlement.clone();
+        }
+
+        let rules = &self.rules;
+        let settlement = site_bounds(self.seed, &rules.settlements, site).and_then(|bounds| {
+            // Rivers are only traced once the village has land to stand on
+            let river_map = OnceCell::new();
+            settlement_at(
+                self.seed,
+                &rules.settlements,
+                site,
+                |pos| rules.settlements.biomes.contains(&self.biome_at(pos).biome),
+                |pos| {
+                    let height = self.height_at(pos);
+                    height > rules.thresholds.sea_level
+                        // Cliffs are never lowland
+                        && ElevationBand::classify(height, &rules.bands) == ElevationBand::Lowland
+                        && !river_map
+                            .get_or_init(|| self.rivers_in(bounds))
+                            .contains(&pos)
+                },
+            )
+        });
+
+        let mut layouts = self.layouts.lock().unwrap();
+        if layouts.settlements.len() >= MAX_CACHED_LAYOUTS {
+            layouts.settlements.clear();
+        }
+        layouts.settlements.insert(site, settlement.clone());
+        settlement
+    }
+
+    pub fn water_at(&self, (x, y): (i32, i32)) -> Option<WaterClass> {
+        self.water_in(GridRect::new(x, y, 1, 1)).remove(&(x, y))
+    }
+
+    pub fn biome_at(&self, pos: (i32, i32)) -> BiomeCell {
+        self.biome_with(pos, self.height_at(pos))
+    }
+
+    /// Biome of `(x, y)`, whose height is already known to be `elevation`
+    fn biome_with(&self, (x, y): (i32, i32), elevation: f64) -> BiomeCell {
+        let rules = &self.rules;
+        let temperature = self.temperature_noise.get([x as f64, y as f64])
+            - elevation.max(0.0) * rules.temperature_lapse;
+        let moisture = self.moisture_noise.get([x as f64, y as f64]);
+
+        BiomeCell {
+            pos: (x, y),
+            elevation,
+            temperature,
+            moisture,
+            biome: Biome::classify(elevation, temperature, moisture, &rules.thresholds),
+            band: ElevationBand::classify(elevation, &rules.bands),
+        }
+    }
+
+    /// Decorations scattered over `rect`, before anything built on the land is cleared
+    fn props_in(&self, rect: GridRect) -> Vec<((i32, i32), &Decoration, usize)> {
+        let rules = &self.rules;
+        scatter(self.seed, rect, rules.max_spacing(), |pos| {
+            let cell = self.biome_at(pos);
+            let decorations = rules.bands.decorations(cell.band).iter();
+            decorations.chain(rules.decorations(cell.biome))
+        })
+    }
+}
+
+impl Tile {
+    pub fn new(pos: (i32, i32), sprite: usize, z_index: i32, seed: u32) -> Self {
+        let puid = gen_puid(seed, z_index, pos);
+        Self {
+            puid,
+            pos,
+            sprite,
+            z_index,
+        }
+    }
+}
+
+// Id of the tile on layer `z_index` at `pos`, the same every time the world is generated from
+// `seed`. The position is packed into 64 bits and only goes through bijective mixes, so two cells
+// of a layer never share an id.
+fn gen_puid(seed: u32, z_index: i32, (x, y): (i32, i32)) -> u64 {
+    let cell = ((x as u32 as u64) << 32) | y as u32 as u64;
+    let h = mix_64(cell.wrapping_add((seed as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)));
+    mix_64(h ^ (z_index as u32 as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f))
+}
@@ -0,0 +1,273 @@
+#[cfg(test)]
+use std::collections::HashSet;
+
+#[cfg(test)]
+use std::sync::{Arc, OnceLock};
+
+#[cfg(test)]
+use crate::{
+    autotile::BLOB_TILE_COUNT, biome::Biome, elevation::ElevationBand, rules::*, water::WaterClass,
+    worldgen::*, *,
+};
+
+// The rect the tests that need a large part of the world look at
+#[cfg(test)]
+pub const WORLD_RECT: GridRect = GridRect {
+    x: -600,
+    y: -500,
+    w: 1200,
+    h: 1000,
+};
+
+/// A generator and the region it generated over `WORLD_RECT`, shared by the tests so that the
+/// large region is only generated once. The generator keeps the villages and roads it laid out.
+#[cfg(test)]
+pub fn test_world() -> &'static (WorldGenerator, Region) {
+    static WORLD: OnceLock<(WorldGenerator, Region)> = OnceLock::new();
+    WORLD.get_or_init(|| {
+        let generator = WorldGenerator::new(1234);
+        let region = generator.generate(WORLD_RECT);
+        (generator, region)
+    })
+}
+
+#[test]
+fn test_chunk_load_order() {
+    let generator = WorldGenerator::new(1234);
+    let chunks = [(0, 0), (1, 0), (0, 1), (-1, -1)];
+
+    let forward: Vec<HashSet<Tile>> = chunks
+        .iter()
+        .map(|c| generator.generate_chunk(*c).tiles.into_iter().collect())
+        .collect();
+    let mut backward: Vec<HashSet<Tile>> = chunks
+        .iter()
+        .rev()
+        .map(|c| generator.generate_chunk(*c).tiles.into_iter().collect())
+        .collect();
+    backward.reverse();
+
+    assert_eq!(forward, backward);
+}
+
+#[test]
+fn test_chunk_seams_match() {
+    let generator = WorldGenerator::new(1234);
+
+    let mut tiles = HashSet::new();
+    let mut ground = HashSet::new();
+    for chunk_pos in [(-1, -1), (0, -1), (-1, 0), (0, 0)] {
+        let region = generator.generate_chunk(chunk_pos);
+        tiles.extend(region.tiles);
+        ground.extend(region.ground);
+    }
+
+    // A rect straddling both seams must agree with the chunks it overlaps
+    let rect = GridRect::new(-30, -25, 60, 50);
+    let region = generator.generate(rect);
+    let expected_tiles: HashSet<Tile> = tiles
+        .iter()
+        .filter(|t| rect.contains(t.pos))
+        .cloned()
+        .collect();
+    let expected_ground: HashSet<(i32, i32)> = ground
+        .iter()
+        .filter(|pos| rect.contains(**pos))
+        .cloned()
+        .collect();
+
+    assert_eq!(
+        region.tiles.into_iter().collect::<HashSet<Tile>>(),
+        expected_tiles
+    );
+    assert_eq!(region.ground, expected_ground);
+}
+
+#[test]
+fn test_rivers_cross_chunks() {
+    let generator = WorldGenerator::new(1234);
+    let rect = GridRect::new(-360, -300, 720, 600);
+    let rivers = generator.rivers_in(rect);
+    assert!(!rivers.is_empty());
+
+    // Every river cell found by a chunk must be found by the larger rect, and vice versa
+    let mut chunked = HashSet::new();
+    for cx in -3..3 {
+        for cy in -3..3 {
+            let region = generator.generate_chunk((cx, cy));
+            assert!(region.rivers.iter().all(|pos| !region.ground.contains(pos)));
+            chunked.extend(region.rivers);
+        }
+    }
+    assert_eq!(chunked, rivers);
+
+    // Rivers are unbroken, every cell touches another one unless it is a lone source
+    let touching = rivers
+        .iter()
+        .filter(|(x, y)| {
+            [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                .iter()
+                .any(|(i, j)| rivers.contains(&(x + i, y + j)))
+        })
+        .count();
+    assert!(touching as f64 > rivers.len() as f64 * 0.95);
+}
+
+#[test]
+fn test_wide_rivers_cross_seams() {
+    // Every river runs to the valley at x = -1 and then south, so the valley is a wide river
+    let rules = RiverRules::default();
+    let height_at = |(x, y): (i32, i32)| 1.0 - 0.001 * y as f64 + 0.01 * (x + 1).abs() as f64;
+    let rivers_in = |rect| river::river_cells(1234, &rules, 0.0, rect, height_at);
+
+    let whole = rivers_in(GridRect::new(-40, 0, 80, 40));
+    assert!(whole.contains(&(-1, 20)) && whole.contains(&(0, 20)));
+
+    let mut halves = rivers_in(GridRect::new(-40, 0, 40, 40));
+    halves.extend(rivers_in(GridRect::new(0, 0, 40, 40)));
+    assert_eq!(halves, whole);
+}
+
+#[test]
+fn test_lakes_are_enclosed() {
+    let (generator, _) = test_world();
+    let water = generator.water_in(WORLD_RECT);
+    let sea_level = generator.rules().thresholds.sea_level;
+    let shallow_level = generator.rules().water.shallow_level;
+
+    let mut lakes = 0;
+    for (pos, class) in water.iter() {
+        let height = generator.height_at(*pos);
+        assert!(height <= sea_level);
+        match class {
+            WaterClass::Shallow => assert!(height > shallow_level),
+            WaterClass::DeepOcean => assert!(height <= shallow_level),
+            WaterClass::Lake => lakes += 1,
+        }
+
+        // Water only ever touches land or water of the same body
+        for (i, j) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            if let Some(nei) = water.get(&(pos.0 + i, pos.1 + j)) {
+                assert_eq!(nei.is_ocean(), class.is_ocean());
+            }
+        }
+    }
+    assert!(lakes > 0);
+
+    // A chunk agrees with a point query
+    let region = generator.generate_chunk((0, 0));
+    for pos in [(0, 0), (17, 42), (119, 99)] {
+        assert_eq!(region.water_at(pos), generator.water_at(pos));
+    }
+}
+
+#[test]
+fn test_beach_is_sand() {
+    let (generator, region) = test_world();
+    let sand_tileset = generator.rules().sand_tileset;
+    assert!(!region.beach.is_empty());
+
+    for pos in region.beach.iter() {
+        assert!(region.ground.contains(pos));
+        assert_eq!(region.biome_at(*pos).unwrap().biome, Biome::Beach);
+    }
+    // Ground tiles of the beach come from the sand tileset
+    for tile in region.tiles.iter() {
+        if tile.z_index != generator.rules().ground_z_index || !region.ground.contains(&tile.pos) {
+            continue;
+        }
+
+        let is_sand = (sand_tileset..sand_tileset + BLOB_TILE_COUNT).contains(&tile.sprite);
+        assert_eq!(is_sand, region.beach.contains(&tile.pos), "{:?}", tile);
+    }
+}
+
+#[test]
+fn test_cliffs_between_bands() {
+    let (generator, region) = test_world();
+    let bands = &generator.rules().bands;
+    assert!(!region.cliffs.is_empty());
+    assert!(region
+        .biomes
+        .iter()
+        .any(|cell| cell.band == ElevationBand::Mountains));
+
+    for &(x, y) in region.cliffs.iter() {
+        assert!(region.ground.contains(&(x, y)));
+        let band = region.biome_at((x, y)).unwrap().band;
+        assert_ne!(band, ElevationBand::Lowland);
+
+        // Every cliff drops into a lower band next to it
+        let height = generator.height_at((x, y));
+        assert!([(0, -1), (1, 0), (0, 1), (-1, 0)].iter().any(|(dx, dy)| {
+            let nei = generator.height_at((x + dx, y + dy));
+            ElevationBand::classify(nei, bands) < band && height - nei > bands.cliff_drop
+        }));
+    }
+    // Nothing else is placed on a cliff
+    for tile in region.tiles.iter() {
+        if region.cliffs.contains(&tile.pos) && tile.z_index > bands.cliff_z_index {
+            panic!("decoration on a cliff {:?}", tile);
+        }
+    }
+}
+
+#[test]
+fn test_tile_puids_are_unique() {
+    // Every cell of the grid, on a few layers
+    let mut puids = HashSet::new();
+    for z_index in 0..3 {
+        for x in 0..GRID_COLS as i32 {
+            for y in 0..GRID_ROWS as i32 {
+                assert!(puids.insert(Tile::new((x, y), 0, z_index, 1234).puid));
+            }
+        }
+    }
+    assert_ne!(
+        Tile::new((3, 4), 0, 0, 1234).puid,
+        Tile::new((3, 4), 0, 0, 1235).puid
+    );
+
+    // No two tiles of a generated region share an id, houses included, and a chunk gives its
+    // tiles the same ids as any region around it
+    let (generator, region) = test_world();
+    let mut ids = HashSet::new();
+    for tile in region.tiles.iter() {
+        assert!(ids.insert(tile.puid), "{:?}", tile);
+    }
+    let tiles: HashSet<&Tile> = region.tiles.iter().collect();
+    for tile in generator.generate_chunk((0, 0)).tiles.iter() {
+        assert!(tiles.contains(tile), "{:?}", tile);
+    }
+}
+
+#[test]
+fn test_island_mode() {
+    let rules = TerrainRules {
+        world: WorldMode::Island(IslandFalloff {
+            shape: FalloffShape::Radial,
+            start: 0.5,
+            depth: 1.5,
+        }),
+        ..Default::default()
+    };
+    let generator = WorldGenerator::with_rules(1234, Arc::new(rules));
+
+    // Open ocean past the edges of the world
+    let outside = GridRect::new(GRID_COLS as i32, 0, 200, 200);
+    assert!(generator.generate(outside).ground.is_empty());
+    let outside = GridRect::new(-300, -300, 200, 200);
+    assert!(generator.generate(outside).ground.is_empty());
+
+    // The middle is left as it is in the infinite world
+    let centre = GridRect::new(
+        GRID_COLS as i32 / 2 - 100,
+        GRID_ROWS as i32 / 2 - 100,
+        200,
+        200,
+    );
+    let island = generator.generate(centre);
+    let infinite = WorldGenerator::new(1234).generate(centre);
+    assert!(!island.ground.is_empty());
+    assert_eq!(island.biomes, infinite.biomes);
+}